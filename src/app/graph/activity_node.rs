@@ -1,5 +1,12 @@
 use std::f32::consts::PI;
 
+use super::{distribution::DurationDistribution, timeline::ActivityState, MAX_THREE_DIGIT_NUMBER};
+
+const TEXT_FIELD_WIDTH: f32 = 100.;
+const TASK_NAME_HEIGHT: f32 = 20.;
+const ACTIVITY_NAME_HEIGHT: f32 = 18.;
+const OUTER_PADDING: egui::Vec2 = egui::vec2(6., 4.);
+
 #[derive(Default, serde::Deserialize, serde::Serialize)]
 pub struct ActivityNode {
     pub pos: egui::Pos2,
@@ -8,6 +15,39 @@ pub struct ActivityNode {
     pub priority: u32,
     pub duration: u32,
     pub remaining_duration: u32,
+    #[serde(default)]
+    pub duration_distribution: DurationDistribution,
+    // released every period ticks, 0 means released whenever it is idle
+    #[serde(default)]
+    pub period: u32,
+    // tick of the first release
+    #[serde(default)]
+    pub phase: u32,
+    // relative to the release, 0 means no deadline
+    #[serde(default)]
+    pub deadline: u32,
+
+    // the duration chosen for the current run
+    #[serde(default)]
+    pub(super) current_duration: u32,
+    // the tick the activity was last started or given a new time slice in
+    #[serde(default)]
+    pub(super) last_start_tick: Option<u64>,
+    // started, but suspended in favor of a higher priority activity
+    #[serde(default)]
+    pub(super) preempted: bool,
+    // the tick the current or next run was released in
+    #[serde(default)]
+    pub(super) release_tick: u64,
+    // released runs that did not start yet
+    #[serde(default)]
+    pub(super) pending_releases: u32,
+    // the current run missed its deadline
+    #[serde(default)]
+    pub(super) deadline_missed: bool,
+    // ticks run since the last start or new time slice
+    #[serde(default)]
+    pub(super) ticks_in_slice: u32,
 
     #[serde(skip)]
     response_outer_id: Option<egui::Id>,
@@ -28,6 +68,17 @@ impl Clone for ActivityNode {
             priority: self.priority,
             duration: self.duration,
             remaining_duration: self.remaining_duration,
+            duration_distribution: self.duration_distribution.clone(),
+            period: self.period,
+            phase: self.phase,
+            deadline: self.deadline,
+            current_duration: self.current_duration,
+            last_start_tick: self.last_start_tick,
+            preempted: self.preempted,
+            release_tick: self.release_tick,
+            pending_releases: self.pending_releases,
+            deadline_missed: self.deadline_missed,
+            ticks_in_slice: self.ticks_in_slice,
             response_outer_id: None,
             response_circle_id: None,
             response_task_name_id: None,
@@ -44,6 +95,79 @@ impl ActivityNode {
         }
     }
 
+    pub fn simulation_state(&self) -> ActivityState {
+        ActivityState {
+            remaining_duration: self.remaining_duration,
+            current_duration: self.current_duration,
+            last_start_tick: self.last_start_tick,
+            preempted: self.preempted,
+            release_tick: self.release_tick,
+            pending_releases: self.pending_releases,
+            deadline_missed: self.deadline_missed,
+            ticks_in_slice: self.ticks_in_slice,
+        }
+    }
+
+    pub fn restore_simulation_state(&mut self, state: &ActivityState) {
+        self.remaining_duration = state.remaining_duration;
+        self.current_duration = state.current_duration;
+        self.last_start_tick = state.last_start_tick;
+        self.preempted = state.preempted;
+        self.release_tick = state.release_tick;
+        self.pending_releases = state.pending_releases;
+        self.deadline_missed = state.deadline_missed;
+        self.ticks_in_slice = state.ticks_in_slice;
+    }
+
+    pub fn is_periodic(&self) -> bool {
+        self.period > 0
+    }
+
+    pub fn is_released_in(&self, tick: u64) -> bool {
+        self.is_periodic()
+            && tick >= self.phase as u64
+            && (tick - self.phase as u64) % self.period as u64 == 0
+    }
+
+    // the current run was released, but did not finish yet
+    pub fn has_unfinished_run(&self) -> bool {
+        self.remaining_duration > 0 || self.pending_releases > 0
+    }
+
+    // the absolute deadline of the current run
+    pub fn absolute_deadline(&self) -> Option<u64> {
+        match self.deadline {
+            0 => None,
+            deadline => Some(self.release_tick + deadline as u64),
+        }
+    }
+
+    // the duration of the current run, the fixed duration if it was not started by the simulation
+    pub fn run_duration(&self) -> u32 {
+        match self.current_duration {
+            0 => self.duration,
+            current_duration => current_duration,
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.remaining_duration > 0 && !self.preempted
+    }
+
+    // where a line from the given point to the center crosses the outline, ignoring the rounding
+    pub fn outline_point(&self, from_point: egui::Pos2) -> egui::Pos2 {
+        let half_size = 0.5
+            * (egui::vec2(TEXT_FIELD_WIDTH, TASK_NAME_HEIGHT + ACTIVITY_NAME_HEIGHT)
+                + 2. * OUTER_PADDING);
+        let to_from_vector = from_point - self.pos;
+        let scale =
+            (to_from_vector.x.abs() / half_size.x).max(to_from_vector.y.abs() / half_size.y);
+        match scale > 1. {
+            true => self.pos + to_from_vector / scale,
+            false => from_point,
+        }
+    }
+
     pub fn interact(&mut self, ui: &egui::Ui) -> Option<egui::Response> {
         if let (
             Some(Some(response_outer)),
@@ -82,28 +206,29 @@ impl ActivityNode {
         ui: &mut egui::Ui,
         container_transform: egui::emath::TSTransform,
         tick_progress: f32,
-    ) {
-        const MAX_THREE_DIGIT_NUMBER: u32 = 999;
+        highlighted: bool,
+        effective_priority: Option<u32>,
+    ) -> bool {
         let style = ui.style().visuals.widgets.inactive;
 
-        let outline_stoke = match self.remaining_duration {
-            0 => egui::Stroke::new(2., egui::Color32::RED),
-            _ => egui::Stroke::new(2.5, egui::Color32::GREEN),
+        let outline_stoke = match (self.remaining_duration, self.preempted) {
+            (0, _) => egui::Stroke::new(2., egui::Color32::RED),
+            (_, true) => egui::Stroke::new(2.5, egui::Color32::from_rgb(255, 140, 0)),
+            (_, false) => egui::Stroke::new(2.5, egui::Color32::GREEN),
+        };
+        let tick_progress = match self.preempted {
+            true => 0.,
+            false => tick_progress,
         };
 
-        let text_field_width = 100.;
-
-        let task_name_height = 20.;
-        let activity_name_height = 18.;
         let textinput_height = 15.;
 
         let task_name_font = egui::FontId::proportional(18.);
         let activity_name_font = egui::FontId::proportional(15.5);
 
-        let outer_padding = egui::vec2(6., 4.);
         let outer_size_without_padding =
-            egui::vec2(text_field_width, task_name_height + activity_name_height);
-        let outer_size = outer_size_without_padding + 2. * outer_padding;
+            egui::vec2(TEXT_FIELD_WIDTH, TASK_NAME_HEIGHT + ACTIVITY_NAME_HEIGHT);
+        let outer_size = outer_size_without_padding + 2. * OUTER_PADDING;
         let outer_rect = egui::Rect::from_center_size(self.pos, outer_size);
         let outer_rounding = 10.;
         let priority_rect = egui::Rect::from_two_pos(
@@ -134,7 +259,7 @@ impl ActivityNode {
             let mut progress_rect = outer_rect;
             progress_rect.set_width(
                 (1. - (self.remaining_duration as f32 - tick_progress)
-                    / (self.duration as f32 - 0.5))
+                    / (self.run_duration() as f32 - 0.5))
                     * outer_rect.width(),
             );
             let height = outer_rect.height() - outer_rounding
@@ -160,12 +285,12 @@ impl ActivityNode {
             egui::Rect::from_center_size(
                 self.pos
                     - egui::vec2(
-                        (circle_radius + outer_padding.x / 2.) / 2.,
-                        (outer_size_without_padding.y - task_name_height) / 2.,
+                        (circle_radius + OUTER_PADDING.x / 2.) / 2.,
+                        (outer_size_without_padding.y - TASK_NAME_HEIGHT) / 2.,
                     ),
                 egui::vec2(
-                    text_field_width - circle_radius - outer_padding.x / 2.,
-                    task_name_height,
+                    TEXT_FIELD_WIDTH - circle_radius - OUTER_PADDING.x / 2.,
+                    TASK_NAME_HEIGHT,
                 ),
             ),
             egui::TextEdit::singleline(&mut self.task_name)
@@ -181,9 +306,9 @@ impl ActivityNode {
                 self.pos
                     + egui::vec2(
                         0.,
-                        (outer_size_without_padding.y - activity_name_height) / 2.,
+                        (outer_size_without_padding.y - ACTIVITY_NAME_HEIGHT) / 2.,
                     ),
-                egui::vec2(text_field_width, activity_name_height),
+                egui::vec2(TEXT_FIELD_WIDTH, ACTIVITY_NAME_HEIGHT),
             ),
             egui::TextEdit::singleline(&mut self.activity_name)
                 .margin(egui::Margin::ZERO)
@@ -199,6 +324,22 @@ impl ActivityNode {
             .rect_stroke(priority_rect, priority_rounding, style.fg_stroke);
         ui.painter()
             .rect_stroke(outer_rect, outer_rounding, outline_stoke);
+        if self.deadline_missed {
+            ui.painter().text(
+                outer_rect.left_top() + egui::vec2(outer_rounding / 2., 0.),
+                egui::Align2::CENTER_CENTER,
+                "⚠",
+                egui::FontId::proportional(16.),
+                egui::Color32::RED,
+            );
+        }
+        if highlighted {
+            ui.painter().rect_stroke(
+                outer_rect.expand(4.),
+                outer_rounding + 4.,
+                egui::Stroke::new(2., egui::Color32::YELLOW),
+            );
+        }
         ui.painter()
             .circle_filled(circle_position, circle_radius, style.bg_fill);
         ui.painter()
@@ -206,7 +347,7 @@ impl ActivityNode {
 
         // Priority
         let speed = container_transform.scaling * 0.05;
-        ui.put(
+        let response_priority = ui.put(
             egui::Rect::from_center_size(
                 priority_rect.center(),
                 egui::Vec2::splat(textinput_height),
@@ -217,6 +358,19 @@ impl ActivityNode {
                 .clamp_range(0..=MAX_THREE_DIGIT_NUMBER),
         );
 
+        // Effective Priority, left of the priority while it is raised
+        if let Some(effective_priority) =
+            effective_priority.filter(|effective_priority| *effective_priority != self.priority)
+        {
+            ui.painter().text(
+                priority_rect.left_center() - egui::vec2(4., 0.),
+                egui::Align2::RIGHT_CENTER,
+                format!("↑{}", effective_priority),
+                egui::FontId::proportional(12.),
+                egui::Color32::from_rgb(255, 140, 0),
+            );
+        }
+
         // Line between remaining duration and duration
         ui.painter().line_segment(
             [
@@ -227,7 +381,7 @@ impl ActivityNode {
         );
 
         // Duration
-        ui.put(
+        let response_duration = ui.put(
             egui::Rect::from_center_size(
                 circle_position + egui::vec2(0., 0.53 * textinput_height),
                 egui::Vec2::splat(textinput_height),
@@ -239,7 +393,7 @@ impl ActivityNode {
         );
 
         // Remaining Duration
-        ui.put(
+        let response_remaining_duration = ui.put(
             egui::Rect::from_center_size(
                 circle_position + egui::vec2(0., -0.53 * textinput_height),
                 egui::Vec2::splat(textinput_height),
@@ -249,5 +403,11 @@ impl ActivityNode {
                 .speed(speed)
                 .clamp_range(0..=MAX_THREE_DIGIT_NUMBER),
         );
+
+        response_task_name.changed()
+            || response_activity_name.changed()
+            || response_priority.changed()
+            || response_duration.changed()
+            || response_remaining_duration.changed()
     }
 }
@@ -0,0 +1,370 @@
+use std::collections::{BTreeMap, HashSet, VecDeque};
+
+use super::{
+    timeline::{ActivityState, BarrierState, MutexState, QueueState},
+    ActivityNodeId, BarrierNodeId, Graph, MutexNodeId, QueueNodeId, SchedulingPolicy,
+};
+
+// a marking of the graph, without anything that only matters for tie breaking
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct State {
+    pub activities: Vec<(ActivityNodeId, ActivityState)>,
+    pub mutexes: Vec<(MutexNodeId, MutexState)>,
+    pub barriers: Vec<(BarrierNodeId, BarrierState)>,
+    pub queues: Vec<(QueueNodeId, QueueState)>,
+    // only relevant if there are periodic activities or mutexes, then repeats every hyperperiod
+    pub tick: Option<u64>,
+}
+
+pub struct Analysis {
+    pub state_count: usize,
+    pub transition_count: usize,
+    // false if the exploration stopped at the state limit
+    pub complete: bool,
+    pub deadlock_states: Vec<State>,
+    pub max_mutex_values: BTreeMap<MutexNodeId, u32>,
+}
+
+impl Analysis {
+    // None if the state space was not explored completely and the bound was not exceeded so far
+    pub fn can_exceed(&self, mutex_id: MutexNodeId, bound: u32) -> Option<bool> {
+        let exceeded = self
+            .max_mutex_values
+            .get(&mutex_id)
+            .map_or(false, |max_value| *max_value > bound);
+        match (exceeded, self.complete) {
+            (true, _) => Some(true),
+            (false, true) => Some(false),
+            (false, false) => None,
+        }
+    }
+}
+
+// an exploration of the state space that can be continued step by step
+pub struct Exploration {
+    graph: Graph,
+    max_states: usize,
+    visited: HashSet<State>,
+    queue: VecDeque<State>,
+    analysis: Analysis,
+}
+
+impl Exploration {
+    // explores up to the given amount of states, returns true once it is finished
+    pub fn step(&mut self, states: usize) -> bool {
+        for _ in 0..states {
+            let Some(state) = self.queue.pop_front() else {
+                return true;
+            };
+            self.analysis.state_count += 1;
+            for (mutex_id, mutex_state) in &state.mutexes {
+                let max_value = self.analysis.max_mutex_values.entry(*mutex_id).or_default();
+                *max_value = (*max_value).max(mutex_state.value);
+            }
+
+            self.graph.apply_analysis_state(&state);
+            let mut deadlocked = false;
+            for mut successor in self.graph.tick_a_successors() {
+                deadlocked |= successor.detect_deadlock().is_some();
+                successor.tick_b();
+                let successor_state = successor.analysis_state();
+                self.analysis.transition_count += 1;
+                if self.visited.contains(&successor_state) {
+                    continue;
+                }
+                if self.visited.len() >= self.max_states {
+                    self.analysis.complete = false;
+                    continue;
+                }
+                self.visited.insert(successor_state.clone());
+                self.queue.push_back(successor_state);
+            }
+            if deadlocked {
+                self.analysis.deadlock_states.push(state);
+            }
+        }
+        self.queue.is_empty()
+    }
+
+    // the number of explored states and the number of states that are waiting to be explored
+    pub fn progress(&self) -> (usize, usize) {
+        (self.analysis.state_count, self.queue.len())
+    }
+
+    pub fn max_states(&self) -> usize {
+        self.max_states
+    }
+
+    // the results so far, incomplete if the exploration is not finished
+    pub fn into_analysis(mut self) -> Analysis {
+        if !self.queue.is_empty() {
+            self.analysis.complete = false;
+        }
+        self.analysis
+    }
+}
+
+impl Graph {
+    /// Explores every state reachable from the current one, following every possible
+    /// order of activities with equal precedence. Stops after `max_states` states.
+    pub fn explore_state_space(&self, max_states: usize) -> Analysis {
+        let mut exploration = self.start_exploration(max_states);
+        while !exploration.step(usize::MAX) {}
+        exploration.into_analysis()
+    }
+
+    /// Prepares an exploration of the state space that is run by calling `Exploration::step`,
+    /// so it can be spread over several frames.
+    pub fn start_exploration(&self, max_states: usize) -> Exploration {
+        let graph = self.clone();
+        let initial_state = graph.analysis_state();
+        Exploration {
+            graph,
+            max_states,
+            visited: HashSet::from([initial_state.clone()]),
+            queue: VecDeque::from([initial_state]),
+            analysis: Analysis {
+                state_count: 0,
+                transition_count: 0,
+                complete: true,
+                deadlock_states: Vec::new(),
+                max_mutex_values: BTreeMap::new(),
+            },
+        }
+    }
+
+    fn analysis_state(&self) -> State {
+        let mut activities = self
+            .activity_nodes
+            .iter()
+            .map(|(id, node)| {
+                let mut state = node.simulation_state();
+                state.last_start_tick = None;
+                state.release_tick = 0;
+                state.deadline_missed = false;
+                if state.remaining_duration == 0 {
+                    state.current_duration = 0;
+                }
+                // releases of activities without a period only matter for their deadline
+                if !node.is_periodic() {
+                    state.pending_releases = 0;
+                }
+                // only matters for round robin, and only until the time slice expired
+                state.ticks_in_slice = match self.scheduling_policy {
+                    SchedulingPolicy::RoundRobin => state.ticks_in_slice.min(self.quantum),
+                    _ => 0,
+                };
+                (*id, state)
+            })
+            .collect::<Vec<_>>();
+        activities.sort_by_key(|(id, _)| *id);
+        let mut mutexes = self
+            .mutex_nodes
+            .iter()
+            .map(|(id, node)| (*id, node.simulation_state()))
+            .collect::<Vec<_>>();
+        mutexes.sort_by_key(|(id, _)| *id);
+        let mut barriers = self
+            .barrier_nodes
+            .iter()
+            .map(|(id, node)| (*id, node.simulation_state()))
+            .collect::<Vec<_>>();
+        barriers.sort_by_key(|(id, _)| *id);
+        let mut queues = self
+            .queue_nodes
+            .iter()
+            .map(|(id, node)| {
+                let mut state = node.simulation_state();
+                // only shown, it does not affect the simulation
+                state.received.clear();
+                (*id, state)
+            })
+            .collect::<Vec<_>>();
+        queues.sort_by_key(|(id, _)| *id);
+        State {
+            activities,
+            mutexes,
+            barriers,
+            queues,
+            tick: self.analysis_tick(),
+        }
+    }
+
+    fn apply_analysis_state(&mut self, state: &State) {
+        self.apply_simulation_state(
+            &state.activities,
+            &state.mutexes,
+            &state.barriers,
+            &state.queues,
+        );
+        if let Some(tick) = state.tick {
+            self.current_tick = tick;
+        }
+    }
+
+    // the current tick, reduced to the hyperperiod once all periodic activities and mutexes
+    // were released or refilled
+    fn analysis_tick(&self) -> Option<u64> {
+        let periodic_activities = self
+            .activity_nodes
+            .values()
+            .filter(|activity_node| activity_node.is_periodic())
+            .map(|activity_node| (activity_node.phase, activity_node.period));
+        let periodic_mutexes = self
+            .mutex_nodes
+            .values()
+            .filter(|mutex_node| mutex_node.is_periodic())
+            .map(|mutex_node| (mutex_node.phase, mutex_node.period));
+        let (max_phase, hyperperiod) = periodic_activities.chain(periodic_mutexes).fold(
+            None,
+            |periods: Option<(u64, u64)>, (phase, period)| {
+                let (max_phase, hyperperiod) = periods.unwrap_or((0, 1));
+                Some((
+                    max_phase.max(phase as u64),
+                    least_common_multiple(hyperperiod, period as u64),
+                ))
+            },
+        )?;
+        Some(match self.current_tick.checked_sub(max_phase) {
+            Some(ticks_since_max_phase) => max_phase + ticks_since_max_phase % hyperperiod,
+            None => self.current_tick,
+        })
+    }
+
+    // every distinct result of tick_a, one for each relevant order of equal priorities
+    fn tick_a_successors(&self) -> Vec<Graph> {
+        let mut graph = self.clone();
+        graph.refill_mutexes();
+        graph.release_activities();
+        graph.suspend_for_preemption();
+
+        // running activities are not affected by the order
+        let groups = match graph.scheduling_policy {
+            // the order depends on ticks, which are not part of the state
+            SchedulingPolicy::EarliestDeadlineFirst | SchedulingPolicy::RoundRobin => {
+                vec![graph
+                    .activity_nodes
+                    .keys()
+                    .copied()
+                    .filter(|id| !graph.activity_nodes[id].is_running())
+                    .collect::<Vec<_>>()]
+            }
+            // preempted activities are resumed before idle ones of the same priority are started
+            _ => graph
+                .precedence_groups()
+                .into_iter()
+                .flat_map(|group| {
+                    let (preempted, idle) = group
+                        .into_iter()
+                        .filter(|id| !graph.activity_nodes[id].is_running())
+                        .partition::<Vec<_>, _>(|id| graph.activity_nodes[id].preempted);
+                    [preempted, idle]
+                })
+                .collect::<Vec<_>>(),
+        }
+        .into_iter()
+        .filter(|group| !group.is_empty())
+        .collect::<Vec<_>>();
+        let mut visited = HashSet::new();
+        let mut successors = Vec::new();
+        let mut successor_states = HashSet::new();
+        graph.explore_orders(
+            &groups,
+            0,
+            groups.first().cloned().unwrap_or_default(),
+            &mut visited,
+            &mut |graph| {
+                for graph in graph.duration_variants() {
+                    if successor_states.insert(graph.analysis_state()) {
+                        successors.push(graph);
+                    }
+                }
+            },
+        );
+        successors
+    }
+
+    // one graph for every combination of durations the activities started in this tick can take
+    fn duration_variants(self) -> Vec<Graph> {
+        let started_activities = self
+            .activity_nodes
+            .iter()
+            .filter(|(_, activity_node)| {
+                activity_node.last_start_tick == Some(self.current_tick)
+                    && activity_node.remaining_duration == activity_node.current_duration
+            })
+            .map(|(id, activity_node)| {
+                (
+                    *id,
+                    activity_node
+                        .duration_distribution
+                        .possible_durations(activity_node.duration),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let mut variants = vec![self];
+        for (activity_id, durations) in started_activities {
+            if durations.len() < 2 {
+                continue;
+            }
+            let mut next_variants = Vec::new();
+            for variant in variants {
+                for duration in &durations {
+                    let mut graph = variant.clone();
+                    let activity_node = &mut graph.activity_nodes[&activity_id];
+                    activity_node.current_duration = *duration;
+                    activity_node.remaining_duration = *duration;
+                    next_variants.push(graph);
+                }
+            }
+            variants = next_variants;
+        }
+        variants
+    }
+
+    fn explore_orders(
+        &self,
+        groups: &[Vec<ActivityNodeId>],
+        group_index: usize,
+        remaining: Vec<ActivityNodeId>,
+        visited: &mut HashSet<(State, usize, Vec<ActivityNodeId>)>,
+        on_result: &mut dyn FnMut(Graph),
+    ) {
+        if remaining.is_empty() {
+            match groups.get(group_index + 1) {
+                Some(next_group) => self.explore_orders(
+                    groups,
+                    group_index + 1,
+                    next_group.clone(),
+                    visited,
+                    on_result,
+                ),
+                None => on_result(self.clone()),
+            }
+            return;
+        }
+
+        // different orders often lead to the same intermediate state
+        if !visited.insert((self.analysis_state(), group_index, remaining.clone())) {
+            return;
+        }
+
+        for (i, activity_id) in remaining.iter().enumerate() {
+            let mut graph = self.clone();
+            graph.schedule_activity(*activity_id);
+            let mut rest = remaining.clone();
+            rest.remove(i);
+            graph.explore_orders(groups, group_index, rest, visited, on_result);
+        }
+    }
+}
+
+fn least_common_multiple(a: u64, b: u64) -> u64 {
+    let mut x = a;
+    let mut y = b;
+    while y != 0 {
+        (x, y) = (y, x % y);
+    }
+    (a / x).saturating_mul(b)
+}
@@ -0,0 +1,184 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::{timeline::BarrierState, ActivityNodeId};
+
+#[derive(Default, serde::Deserialize, serde::Serialize)]
+pub struct BarrierNode {
+    pub pos: egui::Pos2,
+    // arrivals needed to release the waiting activities, at most one per arriving activity
+    pub count: u32,
+    // arriving activities that finished since the last release
+    pub arrived: BTreeSet<ActivityNodeId>,
+    // activities that arrive at the barrier when they finish
+    pub arriving: BTreeSet<ActivityNodeId>,
+    // activities that can only start once the barrier released them
+    pub waiting: BTreeSet<ActivityNodeId>,
+    // releases that were not used by the waiting activities yet
+    pub passes: BTreeMap<ActivityNodeId, u32>,
+
+    #[serde(skip)]
+    response_outer_id: Option<egui::Id>,
+    #[serde(skip)]
+    response_value_id: Option<egui::Id>,
+}
+
+impl Clone for BarrierNode {
+    fn clone(&self) -> Self {
+        Self {
+            pos: self.pos,
+            count: self.count,
+            arrived: self.arrived.clone(),
+            arriving: self.arriving.clone(),
+            waiting: self.waiting.clone(),
+            passes: self.passes.clone(),
+            response_outer_id: None,
+            response_value_id: None,
+        }
+    }
+}
+
+impl BarrierNode {
+    pub fn new(pos: egui::Pos2) -> Self {
+        Self {
+            pos,
+            count: 1,
+            ..Default::default()
+        }
+    }
+
+    pub fn has_pass(&self, activity_id: ActivityNodeId) -> bool {
+        self.passes
+            .get(&activity_id)
+            .map_or(false, |passes| *passes > 0)
+    }
+
+    pub fn take_pass(&mut self, activity_id: ActivityNodeId) {
+        if let Some(passes) = self.passes.get_mut(&activity_id) {
+            *passes = passes.saturating_sub(1);
+            if *passes == 0 {
+                self.passes.remove(&activity_id);
+            }
+        }
+    }
+
+    // the count follows the number of arriving activities, unless it was lowered
+    pub fn toggle_arriving(&mut self, activity_id: ActivityNodeId) {
+        let follows_arriving = self.count >= self.arriving.len() as u32;
+        if !self.arriving.remove(&activity_id) {
+            self.arriving.insert(activity_id);
+        }
+        self.arrived.remove(&activity_id);
+        self.count = match follows_arriving {
+            true => self.arriving.len() as u32,
+            false => self.count.min(self.arriving.len() as u32),
+        }
+        .max(1);
+    }
+
+    // returns true if this arrival released the waiting activities
+    // an activity that finishes again before the release only arrives once
+    pub fn arrive(&mut self, activity_id: ActivityNodeId) -> bool {
+        self.arrived.insert(activity_id);
+        if (self.arrived.len() as u32) < self.count.max(1) {
+            return false;
+        }
+        self.arrived.clear();
+        for activity_id in &self.waiting {
+            *self.passes.entry(*activity_id).or_default() += 1;
+        }
+        true
+    }
+
+    pub fn remove_activity(&mut self, activity_id: ActivityNodeId) {
+        if self.arriving.contains(&activity_id) {
+            self.toggle_arriving(activity_id);
+        }
+        self.waiting.remove(&activity_id);
+        self.passes.remove(&activity_id);
+    }
+
+    pub fn simulation_state(&self) -> BarrierState {
+        BarrierState {
+            arrived: self.arrived.clone(),
+            passes: self.passes.clone(),
+        }
+    }
+
+    pub fn restore_simulation_state(&mut self, state: &BarrierState) {
+        self.arrived = state.arrived.clone();
+        self.passes = state.passes.clone();
+    }
+
+    pub fn interact(&mut self, ui: &egui::Ui) -> Option<egui::Response> {
+        if let (Some(Some(response_outer)), Some(Some(response_value))) = (
+            self.response_outer_id
+                .map(|response_outer_id| ui.ctx().read_response(response_outer_id)),
+            self.response_value_id
+                .map(|response_value_id| ui.ctx().read_response(response_value_id)),
+        ) {
+            if !ui.ctx().input(|i| i.pointer.secondary_down())
+                && (response_outer.dragged() || response_outer.drag_stopped())
+            {
+                self.pos += response_outer.drag_delta();
+                response_value.surrender_focus();
+            }
+
+            Some(response_outer | response_value)
+        } else {
+            None
+        }
+    }
+
+    pub fn draw(
+        &mut self,
+        ui: &mut egui::Ui,
+        container_transform: egui::emath::TSTransform,
+        highlighted: bool,
+    ) -> bool {
+        let style = ui.ctx().style().visuals.widgets.inactive;
+
+        let mut ui = ui.child_ui(ui.max_rect(), *ui.layout());
+
+        // a wide bar with a thick line on each side, like a gate
+        let outer_rect = egui::Rect::from_center_size(self.pos, egui::vec2(44., 30.));
+
+        let mut stroke = style.fg_stroke;
+        if !self.arrived.is_empty() {
+            stroke.color = egui::Color32::GREEN;
+            stroke.width = 1.5;
+        }
+        ui.painter().rect_filled(outer_rect, 0., style.bg_fill);
+        ui.painter().rect_stroke(outer_rect, 0., stroke);
+        for x in [outer_rect.left() + 4., outer_rect.right() - 4.] {
+            ui.painter().line_segment(
+                [
+                    egui::pos2(x, outer_rect.top()),
+                    egui::pos2(x, outer_rect.bottom()),
+                ],
+                egui::Stroke::new(3., stroke.color),
+            );
+        }
+        if highlighted {
+            ui.painter().rect_stroke(
+                outer_rect.expand(4.),
+                0.,
+                egui::Stroke::new(2., egui::Color32::YELLOW),
+            );
+        }
+        let response_outer = ui.allocate_rect(outer_rect, egui::Sense::click_and_drag());
+        self.response_outer_id = Some(response_outer.id);
+
+        // shows the arrivals, but edits the count
+        let response_value = ui.put(
+            egui::Rect::from_center_size(self.pos, egui::vec2(28., 15.)),
+            egui::DragValue::new(&mut self.count)
+                .update_while_editing(false)
+                .clamp_range(1..=self.arriving.len().max(1) as u32)
+                .prefix(format!("{}/", self.arrived.len()))
+                .speed(container_transform.scaling * 0.05),
+        );
+        self.response_value_id = Some(response_value.id);
+
+        response_value.changed()
+    }
+}
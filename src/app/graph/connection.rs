@@ -5,6 +5,93 @@ pub enum Direction {
     TwoWay,
 }
 
+// how the mutex to activity side of a connection affects the activity
+#[derive(Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum InputKind {
+    // takes the weight from the mutex when the activity starts
+    Consume,
+    // only lets the activity start while the mutex holds less than the weight, takes nothing
+    Inhibitor,
+    // only lets the activity start while the mutex holds at least the weight, takes nothing
+    Read,
+}
+
+impl Default for InputKind {
+    fn default() -> Self {
+        Self::Consume
+    }
+}
+
+impl InputKind {
+    pub const ALL: [Self; 3] = [Self::Consume, Self::Inhibitor, Self::Read];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Consume => "Consume",
+            Self::Inhibitor => "Inhibitor",
+            Self::Read => "Read",
+        }
+    }
+
+    // whether the activity can start, as far as this connection is concerned
+    pub fn allows_start(&self, value: u32, weight: u32) -> bool {
+        match self {
+            Self::Consume | Self::Read => value >= weight,
+            Self::Inhibitor => value < weight,
+        }
+    }
+
+    // units taken from the mutex when the activity starts
+    pub fn consumed_units(&self, weight: u32) -> u32 {
+        match self {
+            Self::Consume => weight,
+            Self::Inhibitor | Self::Read => 0,
+        }
+    }
+}
+
+// how the activity to mutex side of a connection affects the mutex
+#[derive(Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum OutputKind {
+    // adds the weight to the mutex when the activity finishes
+    Produce,
+    // sets the mutex to zero when the activity finishes
+    Reset,
+}
+
+impl Default for OutputKind {
+    fn default() -> Self {
+        Self::Produce
+    }
+}
+
+impl OutputKind {
+    pub const ALL: [Self; 2] = [Self::Produce, Self::Reset];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Produce => "Produce",
+            Self::Reset => "Reset",
+        }
+    }
+
+    // units added to the mutex when the activity finishes
+    pub fn produced_units(&self, weight: u32) -> u32 {
+        match self {
+            Self::Produce => weight,
+            Self::Reset => 0,
+        }
+    }
+
+    // the value of the mutex after the activity finished
+    pub fn apply(&self, value: u32, weight: u32) -> u32 {
+        match self {
+            Self::Produce => value.saturating_add(weight),
+            Self::Reset => 0,
+        }
+    }
+}
+
 #[derive(Clone, Copy, serde::Serialize, serde::Deserialize)]
 enum MutexToActivityState {
     Uncharged,
@@ -21,6 +108,7 @@ enum ActivityToMutexState {
     Forwarding,
 }
 
+#[derive(Clone, Copy)]
 pub enum Color {
     Default,
     Active,
@@ -54,10 +142,25 @@ impl From<Color> for Vec<egui::Color32> {
     }
 }
 
+fn default_weight() -> u32 {
+    1
+}
+
 #[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub struct Connection {
     direction: Direction,
 
+    // units consumed when the activity starts
+    #[serde(default = "default_weight")]
+    input_weight: u32,
+    // units produced when the activity finishes
+    #[serde(default = "default_weight")]
+    output_weight: u32,
+    #[serde(default)]
+    input_kind: InputKind,
+    #[serde(default)]
+    output_kind: OutputKind,
+
     activity_to_mutex_state: ActivityToMutexState,
     mutex_to_activity_state: MutexToActivityState,
 }
@@ -66,6 +169,10 @@ impl Connection {
     pub fn new(direction: Direction) -> Self {
         Self {
             direction,
+            input_weight: 1,
+            output_weight: 1,
+            input_kind: InputKind::Consume,
+            output_kind: OutputKind::Produce,
             activity_to_mutex_state: ActivityToMutexState::Uncharged,
             mutex_to_activity_state: MutexToActivityState::Uncharged,
         }
@@ -89,6 +196,45 @@ impl Connection {
         }
     }
 
+    pub fn get_input_weight(&self) -> u32 {
+        self.input_weight
+    }
+
+    pub fn set_input_weight(&mut self, weight: u32) {
+        self.input_weight = weight.max(1);
+    }
+
+    pub fn get_output_weight(&self) -> u32 {
+        self.output_weight
+    }
+
+    pub fn set_output_weight(&mut self, weight: u32) {
+        self.output_weight = weight.max(1);
+    }
+
+    pub fn get_input_kind(&self) -> InputKind {
+        self.input_kind
+    }
+
+    pub fn set_input_kind(&mut self, input_kind: InputKind) {
+        self.input_kind = input_kind;
+        self.mutex_to_activity_state = MutexToActivityState::Uncharged;
+    }
+
+    pub fn get_output_kind(&self) -> OutputKind {
+        self.output_kind
+    }
+
+    pub fn set_output_kind(&mut self, output_kind: OutputKind) {
+        self.output_kind = output_kind;
+    }
+
+    // copy the animation states of a previously recorded connection
+    pub fn restore_states(&mut self, recorded: &Connection) {
+        self.activity_to_mutex_state = recorded.activity_to_mutex_state;
+        self.mutex_to_activity_state = recorded.mutex_to_activity_state;
+    }
+
     pub fn tick(&mut self, activity_node: &super::ActivityNode, mutex_node: &super::MutexNode) {
         self.tick_mutex_to_activity(activity_node, mutex_node);
         self.tick_activity_to_mutex(activity_node);
@@ -99,18 +245,22 @@ impl Connection {
         activity_node: &super::ActivityNode,
         mutex_node: &super::MutexNode,
     ) {
+        // nothing flows through inputs that do not consume
+        if self.input_kind != InputKind::Consume {
+            return;
+        }
         if self.direction == Direction::MutexToActivity || self.direction == Direction::TwoWay {
             self.mutex_to_activity_state = match (
                 &self.mutex_to_activity_state,
-                mutex_node.value,
-                activity_node.duration == activity_node.remaining_duration,
+                mutex_node.value >= self.input_weight,
+                activity_node.run_duration() == activity_node.remaining_duration,
             ) {
-                (MutexToActivityState::Charging, 0, true) => MutexToActivityState::Forwarding,
-                (MutexToActivityState::Charged, 0, true) => MutexToActivityState::Forwarding,
+                (MutexToActivityState::Charging, false, true) => MutexToActivityState::Forwarding,
+                (MutexToActivityState::Charged, false, true) => MutexToActivityState::Forwarding,
 
-                (MutexToActivityState::Charging, 0, _) => MutexToActivityState::Uncharging,
-                (MutexToActivityState::Charged, 0, _) => MutexToActivityState::Uncharging,
-                (_, 0, _) => MutexToActivityState::Uncharged,
+                (MutexToActivityState::Charging, false, _) => MutexToActivityState::Uncharging,
+                (MutexToActivityState::Charged, false, _) => MutexToActivityState::Uncharging,
+                (_, false, _) => MutexToActivityState::Uncharged,
 
                 (MutexToActivityState::Charging, _, _) => MutexToActivityState::Charged,
                 (MutexToActivityState::Charged, _, _) => MutexToActivityState::Charged,
@@ -181,15 +331,19 @@ impl Connection {
                     activity_to_mutex_color_2,
                     activity_to_mutex_progress,
                 );
+                self.draw_output_label(ui, activity_node.pos, mutex_node.pos);
             }
             Direction::MutexToActivity => {
-                Self::draw_arrow(
+                self.draw_input(
                     ui,
-                    mutex_node.pos,
-                    activity_node.pos,
-                    mutex_to_activity_color_1,
-                    mutex_to_activity_color_2,
-                    mutex_to_activity_progress,
+                    activity_node,
+                    mutex_node,
+                    egui::Vec2::ZERO,
+                    (
+                        mutex_to_activity_color_1,
+                        mutex_to_activity_color_2,
+                        mutex_to_activity_progress,
+                    ),
                 );
             }
             Direction::TwoWay => {
@@ -202,18 +356,148 @@ impl Connection {
                     activity_to_mutex_color_2,
                     activity_to_mutex_progress,
                 );
-                Self::draw_arrow(
+                self.draw_output_label(ui, activity_node.pos + offset, mutex_node.pos + offset);
+                self.draw_input(
                     ui,
-                    mutex_node.pos - offset,
-                    activity_node.pos - offset,
-                    mutex_to_activity_color_1,
-                    mutex_to_activity_color_2,
-                    mutex_to_activity_progress,
+                    activity_node,
+                    mutex_node,
+                    -offset,
+                    (
+                        mutex_to_activity_color_1,
+                        mutex_to_activity_color_2,
+                        mutex_to_activity_progress,
+                    ),
                 );
             }
         }
     }
 
+    // the arrow from the mutex to the activity, depending on the kind of input
+    fn draw_input(
+        &self,
+        ui: &egui::Ui,
+        activity_node: &super::ActivityNode,
+        mutex_node: &super::MutexNode,
+        offset: egui::Vec2,
+        (color_1, color_2, color_progress): (Color, Color, f32),
+    ) {
+        let from_point = mutex_node.pos + offset;
+        let to_point = activity_node.pos + offset;
+        match self.input_kind {
+            InputKind::Consume => {
+                Self::draw_arrow(ui, from_point, to_point, color_1, color_2, color_progress);
+            }
+            InputKind::Inhibitor => {
+                let inhibiting = !self
+                    .input_kind
+                    .allows_start(mutex_node.value, self.input_weight);
+                Self::draw_circle_head_line(
+                    ui,
+                    from_point,
+                    activity_node.outline_point(mutex_node.pos) + offset,
+                    match inhibiting {
+                        true => egui::Color32::RED,
+                        false => egui::Color32::GRAY,
+                    },
+                );
+            }
+            InputKind::Read => {
+                let satisfied = self
+                    .input_kind
+                    .allows_start(mutex_node.value, self.input_weight);
+                Self::draw_dot_head_dashed_line(
+                    ui,
+                    from_point,
+                    activity_node.outline_point(mutex_node.pos) + offset,
+                    match satisfied {
+                        true => egui::Color32::GREEN,
+                        false => egui::Color32::GRAY,
+                    },
+                );
+            }
+        }
+        Self::draw_weight(ui, from_point, to_point, self.input_weight);
+    }
+
+    // a plain line ending in a small circle right at to_point
+    fn draw_circle_head_line(
+        ui: &egui::Ui,
+        from_point: egui::Pos2,
+        to_point: egui::Pos2,
+        color: egui::Color32,
+    ) {
+        const RADIUS: f32 = 4.;
+        const WIDTH: f32 = 2.;
+
+        let circle_center = to_point - (to_point - from_point).normalized() * (RADIUS + WIDTH);
+        ui.painter().line_segment(
+            [
+                from_point,
+                circle_center - (to_point - from_point).normalized() * RADIUS,
+            ],
+            egui::Stroke::new(WIDTH, color),
+        );
+        ui.painter()
+            .circle_stroke(circle_center, RADIUS, egui::Stroke::new(WIDTH, color));
+    }
+
+    // a dashed line ending in a small filled circle right at to_point
+    fn draw_dot_head_dashed_line(
+        ui: &egui::Ui,
+        from_point: egui::Pos2,
+        to_point: egui::Pos2,
+        color: egui::Color32,
+    ) {
+        const RADIUS: f32 = 4.;
+        const WIDTH: f32 = 2.;
+
+        let circle_center = to_point - (to_point - from_point).normalized() * RADIUS;
+        ui.painter().extend(egui::Shape::dashed_line(
+            &[from_point, circle_center],
+            egui::Stroke::new(WIDTH, color),
+            6.,
+            3.,
+        ));
+        ui.painter().circle_filled(circle_center, RADIUS, color);
+    }
+
+    // the weight, or what happens to the mutex instead of adding the weight
+    fn draw_output_label(&self, ui: &egui::Ui, from_point: egui::Pos2, to_point: egui::Pos2) {
+        match self.output_kind {
+            OutputKind::Produce => Self::draw_weight(ui, from_point, to_point, self.output_weight),
+            OutputKind::Reset => Self::draw_label(ui, from_point, to_point, "reset"),
+        }
+    }
+
+    // only if it is not 1
+    fn draw_weight(ui: &egui::Ui, from_point: egui::Pos2, to_point: egui::Pos2, weight: u32) {
+        if weight != 1 {
+            Self::draw_label(ui, from_point, to_point, &weight.to_string());
+        }
+    }
+
+    // next to the middle of the arrow, on its right hand side
+    pub fn draw_label(ui: &egui::Ui, from_point: egui::Pos2, to_point: egui::Pos2, text: &str) {
+        Self::draw_colored_label(ui, from_point, to_point, text, egui::Color32::LIGHT_GRAY);
+    }
+
+    pub fn draw_colored_label(
+        ui: &egui::Ui,
+        from_point: egui::Pos2,
+        to_point: egui::Pos2,
+        text: &str,
+        color: egui::Color32,
+    ) {
+        let from_to_unit_vector = (to_point - from_point).normalized();
+        ui.painter().text(
+            from_point + 0.5 * (to_point - from_point) + from_to_unit_vector.rot90() * 12.,
+            egui::Align2::CENTER_CENTER,
+            text,
+            egui::FontId::proportional(12.),
+            color,
+        );
+    }
+
     pub fn draw_arrow(
         ui: &egui::Ui,
         from_point: egui::Pos2,
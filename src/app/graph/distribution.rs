@@ -0,0 +1,186 @@
+use rand::Rng;
+
+use super::MAX_THREE_DIGIT_NUMBER;
+
+// how the duration of each run of an activity is chosen
+#[derive(Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum DurationDistribution {
+    // always the duration of the activity
+    Fixed,
+    // any duration between min and max, each equally likely
+    Uniform { min: u32, max: u32 },
+    // one of the given durations, chosen by weight
+    Discrete(Vec<(u32, u32)>),
+    // either the best or the worst case, each equally likely
+    BestWorstCase { best: u32, worst: u32 },
+}
+
+impl Default for DurationDistribution {
+    fn default() -> Self {
+        Self::Fixed
+    }
+}
+
+impl DurationDistribution {
+    pub const NAMES: [&'static str; 4] = ["Fixed", "Uniform", "Discrete", "Best/Worst Case"];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Fixed => Self::NAMES[0],
+            Self::Uniform { .. } => Self::NAMES[1],
+            Self::Discrete(_) => Self::NAMES[2],
+            Self::BestWorstCase { .. } => Self::NAMES[3],
+        }
+    }
+
+    // a distribution of the given kind, based on a fixed duration
+    pub fn from_name(name: &str, duration: u32) -> Option<Self> {
+        match Self::NAMES
+            .iter()
+            .position(|known_name| known_name.eq_ignore_ascii_case(name))?
+        {
+            0 => Some(Self::Fixed),
+            1 => Some(Self::Uniform {
+                min: duration,
+                max: duration,
+            }),
+            2 => Some(Self::Discrete(vec![(duration, 1)])),
+            _ => Some(Self::BestWorstCase {
+                best: duration,
+                worst: duration,
+            }),
+        }
+    }
+
+    // every duration that can be chosen, falls back to the fixed duration
+    pub fn possible_durations(&self, duration: u32) -> Vec<u32> {
+        let mut durations = match self {
+            Self::Fixed => return vec![duration],
+            Self::Uniform { min, max } => ((*min).min(*max)..=(*min).max(*max)).collect(),
+            Self::Discrete(options) => options
+                .iter()
+                .filter(|(_, weight)| *weight > 0)
+                .map(|(duration, _)| *duration)
+                .collect(),
+            Self::BestWorstCase { best, worst } => vec![*best, *worst],
+        };
+        durations.retain(|duration| *duration > 0);
+        durations.sort();
+        durations.dedup();
+        if durations.is_empty() {
+            durations.push(duration.max(1));
+        }
+        durations
+    }
+
+    pub fn sample(&self, duration: u32, rng: &mut impl Rng) -> u32 {
+        match self {
+            Self::Fixed => duration,
+            // sampled directly, the range can be too large to list every duration
+            Self::Uniform { min, max } => {
+                let (min, max) = ((*min).min(*max).max(1), (*min).max(*max));
+                match min <= max {
+                    true => rng.gen_range(min..=max),
+                    false => duration.max(1),
+                }
+            }
+            Self::Discrete(options) => {
+                let options = options
+                    .iter()
+                    .filter(|(duration, weight)| *duration > 0 && *weight > 0)
+                    .collect::<Vec<_>>();
+                let total_weight = options
+                    .iter()
+                    .map(|(_, weight)| *weight as u64)
+                    .sum::<u64>();
+                if total_weight == 0 {
+                    return duration.max(1);
+                }
+                let mut choice = rng.gen_range(0..total_weight);
+                for (duration, weight) in options {
+                    if choice < *weight as u64 {
+                        return *duration;
+                    }
+                    choice -= *weight as u64;
+                }
+                duration.max(1)
+            }
+            Self::BestWorstCase { .. } => {
+                let possible_durations = self.possible_durations(duration);
+                possible_durations[rng.gen_range(0..possible_durations.len())]
+            }
+        }
+    }
+
+    // e.g. "Uniform 2 5", "Discrete 2:1 5:3" or "Best/Worst Case 2 5", None for fixed durations
+    pub fn to_text(&self) -> Option<String> {
+        match self {
+            Self::Fixed => None,
+            Self::Uniform { min, max } => Some(format!("{} {} {}", self.name(), min, max)),
+            Self::Discrete(options) => {
+                Some(format!("{} {}", self.name(), options_to_text(options)))
+            }
+            Self::BestWorstCase { best, worst } => {
+                Some(format!("{} {} {}", self.name(), best, worst))
+            }
+        }
+    }
+
+    pub fn from_text(text: &str) -> Option<Self> {
+        let text = text.trim();
+        let name = Self::NAMES.iter().find(|name| {
+            text.get(..name.len())
+                .map_or(false, |prefix| prefix.eq_ignore_ascii_case(name))
+        })?;
+        let parameters = &text[name.len()..];
+        let numbers = || {
+            parameters
+                .split_whitespace()
+                .map(|number| {
+                    let number = number.parse::<u32>().ok()?;
+                    Some(number.clamp(1, MAX_THREE_DIGIT_NUMBER))
+                })
+                .collect::<Option<Vec<_>>>()
+        };
+        match Self::from_name(name, 0)? {
+            Self::Fixed => Some(Self::Fixed),
+            Self::Uniform { .. } => match numbers()?[..] {
+                [min, max] => Some(Self::Uniform { min, max }),
+                _ => None,
+            },
+            Self::Discrete(_) => Some(Self::Discrete(
+                options_from_text(parameters)?
+                    .into_iter()
+                    .map(|(duration, weight)| {
+                        (
+                            duration.min(MAX_THREE_DIGIT_NUMBER),
+                            weight.min(MAX_THREE_DIGIT_NUMBER),
+                        )
+                    })
+                    .collect(),
+            )),
+            Self::BestWorstCase { .. } => match numbers()?[..] {
+                [best, worst] => Some(Self::BestWorstCase { best, worst }),
+                _ => None,
+            },
+        }
+    }
+}
+
+// e.g. "2:1 5:3" for duration 2 with weight 1 and duration 5 with weight 3
+pub fn options_to_text(options: &[(u32, u32)]) -> String {
+    options
+        .iter()
+        .map(|(duration, weight)| format!("{}:{}", duration, weight))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub fn options_from_text(text: &str) -> Option<Vec<(u32, u32)>> {
+    text.split_whitespace()
+        .map(|option| {
+            let (duration, weight) = option.split_once(':')?;
+            Some((duration.parse().ok()?, weight.parse().ok()?))
+        })
+        .collect()
+}
@@ -0,0 +1,180 @@
+use super::{ActivityNodeId, Graph};
+
+const MAX_RECORDED_TICKS: usize = 10_000;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ActivityStatus {
+    Running,
+    Preempted,
+    // ready or released, but blocked or without a free core
+    Waiting,
+    // a periodic activity with nothing to do until its next release
+    Idle,
+}
+
+// what every activity did during one tick
+pub struct TickRecord {
+    pub tick: u64,
+    pub statuses: Vec<(ActivityNodeId, ActivityStatus)>,
+}
+
+#[derive(Default)]
+pub struct Schedule {
+    records: std::collections::VecDeque<TickRecord>,
+}
+
+impl Schedule {
+    // replaces the record of the same tick and discards all later ones
+    pub fn record(&mut self, record: TickRecord) {
+        while self
+            .records
+            .back()
+            .map_or(false, |last| last.tick >= record.tick)
+        {
+            self.records.pop_back();
+        }
+        self.records.push_back(record);
+        if self.records.len() > MAX_RECORDED_TICKS {
+            self.records.pop_front();
+        }
+    }
+
+    // forget the records of the given tick and all later ones
+    pub fn discard_from(&mut self, tick: u64) {
+        while self.records.back().map_or(false, |last| last.tick >= tick) {
+            self.records.pop_back();
+        }
+    }
+
+    pub fn records(&self) -> impl Iterator<Item = &TickRecord> {
+        self.records.iter()
+    }
+}
+
+impl Graph {
+    pub fn gantt_chart_ui(&self, ui: &mut egui::Ui, group_by_task: bool) {
+        const LABEL_WIDTH: f32 = 130.;
+        const HEADER_HEIGHT: f32 = 16.;
+        const ROW_HEIGHT: f32 = 18.;
+        const TICK_WIDTH: f32 = 12.;
+
+        // one row per activity or per task, each with the activities it contains
+        let mut rows: Vec<(String, Vec<ActivityNodeId>)> = Vec::new();
+        let mut activity_ids = self.activity_nodes.keys().copied().collect::<Vec<_>>();
+        activity_ids.sort();
+        for id in activity_ids {
+            let activity_node = &self.activity_nodes[&id];
+            match group_by_task {
+                true => match rows
+                    .iter_mut()
+                    .find(|(label, _)| *label == activity_node.task_name)
+                {
+                    Some((_, ids)) => ids.push(id),
+                    None => rows.push((activity_node.task_name.clone(), vec![id])),
+                },
+                false => rows.push((
+                    format!(
+                        "{}: {}",
+                        activity_node.task_name, activity_node.activity_name
+                    ),
+                    vec![id],
+                )),
+            }
+        }
+
+        let records = self.schedule.records().collect::<Vec<_>>();
+        if rows.is_empty() || records.is_empty() {
+            ui.centered_and_justified(|ui| ui.label("Run the simulation to fill the chart."));
+            return;
+        }
+        let first_tick = records[0].tick;
+
+        egui::ScrollArea::both()
+            .auto_shrink(false)
+            .stick_to_right(true)
+            .show(ui, |ui| {
+                let size = egui::vec2(
+                    LABEL_WIDTH + records.len() as f32 * TICK_WIDTH,
+                    HEADER_HEIGHT + rows.len() as f32 * ROW_HEIGHT,
+                );
+                let (rect, _) = ui.allocate_exact_size(size, egui::Sense::hover());
+                let painter = ui.painter_at(rect);
+                let text_color = ui.visuals().text_color();
+                let font = egui::FontId::proportional(12.);
+                let x_of_tick =
+                    |tick: u64| rect.left() + LABEL_WIDTH + (tick - first_tick) as f32 * TICK_WIDTH;
+
+                // tick numbers
+                for record in records.iter().filter(|record| record.tick % 5 == 0) {
+                    painter.text(
+                        egui::pos2(x_of_tick(record.tick), rect.top()),
+                        egui::Align2::LEFT_TOP,
+                        record.tick.to_string(),
+                        font.clone(),
+                        egui::Color32::GRAY,
+                    );
+                }
+
+                for (row_index, (label, ids)) in rows.iter().enumerate() {
+                    let row_top = rect.top() + HEADER_HEIGHT + row_index as f32 * ROW_HEIGHT;
+                    painter.text(
+                        egui::pos2(rect.left(), row_top + ROW_HEIGHT / 2.),
+                        egui::Align2::LEFT_CENTER,
+                        label,
+                        font.clone(),
+                        text_color,
+                    );
+
+                    for record in &records {
+                        let row_statuses = record
+                            .statuses
+                            .iter()
+                            .filter(|(id, _)| ids.contains(id))
+                            .map(|(_, status)| *status)
+                            .collect::<Vec<_>>();
+                        let status = [
+                            ActivityStatus::Running,
+                            ActivityStatus::Preempted,
+                            ActivityStatus::Waiting,
+                        ]
+                        .into_iter()
+                        .find(|status| row_statuses.contains(status))
+                        .unwrap_or(ActivityStatus::Idle);
+                        let (height, color) = match status {
+                            ActivityStatus::Running => (ROW_HEIGHT - 4., egui::Color32::GREEN),
+                            ActivityStatus::Preempted => {
+                                (ROW_HEIGHT / 2., egui::Color32::from_rgb(255, 140, 0))
+                            }
+                            ActivityStatus::Waiting => {
+                                (ROW_HEIGHT / 4., egui::Color32::from_rgb(140, 30, 30))
+                            }
+                            ActivityStatus::Idle => (1., egui::Color32::DARK_GRAY),
+                        };
+                        painter.rect_filled(
+                            egui::Rect::from_min_size(
+                                egui::pos2(
+                                    x_of_tick(record.tick),
+                                    row_top + (ROW_HEIGHT - height) / 2.,
+                                ),
+                                egui::vec2(TICK_WIDTH, height),
+                            ),
+                            0.,
+                            color,
+                        );
+                    }
+                }
+
+                // current tick
+                if self.current_tick >= first_tick {
+                    let x = x_of_tick(self.current_tick) + TICK_WIDTH * self.tick_progress;
+                    painter.line_segment(
+                        [
+                            egui::pos2(x, rect.top() + HEADER_HEIGHT),
+                            egui::pos2(x, rect.bottom()),
+                        ],
+                        egui::Stroke::new(1., egui::Color32::YELLOW),
+                    );
+                }
+            });
+    }
+}
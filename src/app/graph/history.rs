@@ -0,0 +1,125 @@
+use super::{
+    connection::Connection, ActivityNode, ActivityNodeId, BarrierNode, BarrierNodeId, LockNode,
+    LockNodeId, MutexNode, MutexNodeId, QueueNode, QueueNodeId,
+};
+
+const MAX_UNDO_STEPS: usize = 100;
+
+// a value of the simulation state that was changed by hand, together with its old value
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ValueEdit {
+    MutexValue(MutexNodeId, u32),
+    RemainingDuration(ActivityNodeId, u32),
+}
+
+impl ValueEdit {
+    fn is_same_value(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::MutexValue(a, _), Self::MutexValue(b, _)) => a == b,
+            (Self::RemainingDuration(a, _), Self::RemainingDuration(b, _)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+// The editable structure and properties of the graph. Restoring it keeps the current
+// simulation state, except for the values that were changed by hand during the edit.
+#[derive(Clone)]
+pub struct Snapshot {
+    pub activity_nodes: indexmap::IndexMap<ActivityNodeId, ActivityNode>,
+    pub mutex_nodes: std::collections::HashMap<MutexNodeId, MutexNode>,
+    pub barrier_nodes: std::collections::HashMap<BarrierNodeId, BarrierNode>,
+    pub lock_nodes: std::collections::HashMap<LockNodeId, LockNode>,
+    pub queue_nodes: std::collections::HashMap<QueueNodeId, QueueNode>,
+    pub connections: std::collections::HashMap<
+        ActivityNodeId,
+        std::collections::HashMap<MutexNodeId, Connection>,
+    >,
+    pub next_activity_id: ActivityNodeId,
+    pub next_mutex_id: MutexNodeId,
+    pub next_barrier_id: BarrierNodeId,
+    pub next_lock_id: LockNodeId,
+    pub next_queue_id: QueueNodeId,
+    pub value_edits: Vec<ValueEdit>,
+}
+
+// Edits are grouped by pointer presses: the state after the last group of edits is kept
+// and only pushed to the undo stack once something was edited again, so nothing has to be
+// copied as long as nothing is edited.
+#[derive(Default)]
+pub struct History {
+    undo_stack: Vec<Snapshot>,
+    redo_stack: Vec<Snapshot>,
+    edit_start: Option<Snapshot>,
+    value_edits: Vec<ValueEdit>,
+    edited: bool,
+}
+
+impl History {
+    pub fn has_edit_start(&self) -> bool {
+        self.edit_start.is_some()
+    }
+
+    pub fn set_edit_start(&mut self, current: Snapshot) {
+        self.edit_start = Some(current);
+        self.value_edits.clear();
+        self.edited = false;
+    }
+
+    pub fn mark_edited(&mut self) {
+        self.edited = true;
+    }
+
+    pub fn is_edited(&self) -> bool {
+        self.edited
+    }
+
+    // only the value from before the first change is kept
+    pub fn record_value_edit(&mut self, value_edit: ValueEdit) {
+        if !self
+            .value_edits
+            .iter()
+            .any(|recorded| recorded.is_same_value(&value_edit))
+        {
+            self.value_edits.push(value_edit);
+        }
+    }
+
+    // the current state becomes the start of the next edit
+    pub fn commit_edit(&mut self, current: Snapshot) {
+        let value_edits = std::mem::take(&mut self.value_edits);
+        self.edited = false;
+        if let Some(mut edit_start) = self.edit_start.replace(current) {
+            edit_start.value_edits = value_edits;
+            self.undo_stack.push(edit_start);
+            if self.undo_stack.len() > MAX_UNDO_STEPS {
+                self.undo_stack.remove(0);
+            }
+            self.redo_stack.clear();
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.edited || !self.undo_stack.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.edited && !self.redo_stack.is_empty()
+    }
+
+    pub fn pop_undo(&mut self) -> Option<Snapshot> {
+        self.undo_stack.pop()
+    }
+
+    pub fn pop_redo(&mut self) -> Option<Snapshot> {
+        self.redo_stack.pop()
+    }
+
+    pub fn push_undo(&mut self, snapshot: Snapshot) {
+        self.undo_stack.push(snapshot);
+    }
+
+    pub fn push_redo(&mut self, snapshot: Snapshot) {
+        self.redo_stack.push(snapshot);
+    }
+}
@@ -0,0 +1,130 @@
+use std::collections::BTreeMap;
+
+use super::ActivityNodeId;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, serde::Deserialize, serde::Serialize)]
+pub enum LockAccess {
+    // held together with other shared holders, like readers
+    Shared,
+    // held alone, like a writer
+    Exclusive,
+}
+
+impl Default for LockAccess {
+    fn default() -> Self {
+        Self::Exclusive
+    }
+}
+
+impl LockAccess {
+    pub const ALL: [Self; 2] = [Self::Exclusive, Self::Shared];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Shared => "Shared",
+            Self::Exclusive => "Exclusive",
+        }
+    }
+
+    // whether it can be acquired while the given accesses hold the lock
+    pub fn is_compatible_with(&self, held: impl IntoIterator<Item = LockAccess>) -> bool {
+        let mut held = held.into_iter();
+        match self {
+            Self::Shared => held.all(|access| access == Self::Shared),
+            Self::Exclusive => held.next().is_none(),
+        }
+    }
+}
+
+// activities acquire the lock when they start and release it when they finish,
+// so the holders are the started activities connected to it
+#[derive(Default, serde::Deserialize, serde::Serialize)]
+pub struct LockNode {
+    pub pos: egui::Pos2,
+    pub access: BTreeMap<ActivityNodeId, LockAccess>,
+
+    #[serde(skip)]
+    response_outer_id: Option<egui::Id>,
+}
+
+impl Clone for LockNode {
+    fn clone(&self) -> Self {
+        Self {
+            pos: self.pos,
+            access: self.access.clone(),
+            response_outer_id: None,
+        }
+    }
+}
+
+impl LockNode {
+    pub fn new(pos: egui::Pos2) -> Self {
+        Self {
+            pos,
+            ..Default::default()
+        }
+    }
+
+    pub fn interact(&mut self, ui: &egui::Ui) -> Option<egui::Response> {
+        if let Some(Some(response_outer)) = self
+            .response_outer_id
+            .map(|response_outer_id| ui.ctx().read_response(response_outer_id))
+        {
+            if !ui.ctx().input(|i| i.pointer.secondary_down())
+                && (response_outer.dragged() || response_outer.drag_stopped())
+            {
+                self.pos += response_outer.drag_delta();
+            }
+
+            Some(response_outer)
+        } else {
+            None
+        }
+    }
+
+    pub fn draw(&mut self, ui: &mut egui::Ui, holders: &[LockAccess]) {
+        let style = ui.ctx().style().visuals.widgets.inactive;
+
+        let mut ui = ui.child_ui(ui.max_rect(), *ui.layout());
+
+        // a padlock, the body shows the amount of holders
+        let outer_rect = egui::Rect::from_center_size(self.pos, egui::vec2(30., 24.));
+        let shackle_rect = egui::Rect::from_center_size(
+            outer_rect.center_top() - egui::vec2(0., 6.),
+            egui::vec2(16., 12.),
+        );
+
+        let mut stroke = style.fg_stroke;
+        if holders.contains(&LockAccess::Exclusive) {
+            stroke.color = egui::Color32::RED;
+            stroke.width = 1.5;
+        } else if !holders.is_empty() {
+            stroke.color = egui::Color32::GREEN;
+            stroke.width = 1.5;
+        }
+        ui.painter().line_segment(
+            [shackle_rect.left_bottom(), shackle_rect.left_top()],
+            stroke,
+        );
+        ui.painter()
+            .line_segment([shackle_rect.left_top(), shackle_rect.right_top()], stroke);
+        ui.painter().line_segment(
+            [shackle_rect.right_top(), shackle_rect.right_bottom()],
+            stroke,
+        );
+        ui.painter().rect_filled(outer_rect, 3., style.bg_fill);
+        ui.painter().rect_stroke(outer_rect, 3., stroke);
+        ui.painter().text(
+            outer_rect.center(),
+            egui::Align2::CENTER_CENTER,
+            holders.len().to_string(),
+            egui::FontId::proportional(12.),
+            style.fg_stroke.color,
+        );
+        let response_outer = ui.allocate_rect(
+            outer_rect.union(shackle_rect),
+            egui::Sense::click_and_drag(),
+        );
+        self.response_outer_id = Some(response_outer.id);
+    }
+}
@@ -1,70 +1,255 @@
 mod activity_node;
+mod analysis;
+mod barrier_node;
 pub mod connection;
+mod distribution;
+mod gantt;
+mod history;
+mod lock_node;
 mod mutex_node;
+mod properties;
+mod queue_node;
+mod statistics;
+mod timeline;
+pub mod trace;
+
+#[cfg(test)]
+mod tests;
 
 pub use activity_node::ActivityNode;
+pub use analysis::{Analysis, Exploration};
+pub use barrier_node::BarrierNode;
 use egui::{emath::TSTransform, Pos2};
+pub use lock_node::{LockAccess, LockNode};
 pub use mutex_node::MutexNode;
+pub use queue_node::QueueNode;
 use rand::{thread_rng, Rng as _, SeedableRng as _};
 use random_word::Lang;
 
 use self::connection::Direction;
 
-#[derive(
-    PartialOrd, Ord, Default, Hash, Clone, Copy, Eq, PartialEq, serde::Serialize, serde::Deserialize,
-)]
-pub struct ActivityNodeId(usize);
-impl std::ops::Deref for ActivityNodeId {
-    type Target = usize;
-    fn deref(&self) -> &Self::Target {
-        &self.0
+// the largest value of the number fields, so they stay three digits wide
+pub(super) const MAX_THREE_DIGIT_NUMBER: u32 = 999;
+
+// the ID of a node of one kind, so IDs of different kinds can not be mixed up
+macro_rules! node_id {
+    ($name:ident) => {
+        #[derive(
+            PartialOrd,
+            Ord,
+            Default,
+            Hash,
+            Clone,
+            Copy,
+            Eq,
+            PartialEq,
+            serde::Serialize,
+            serde::Deserialize,
+        )]
+        pub struct $name(usize);
+        impl std::ops::Deref for $name {
+            type Target = usize;
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+        impl std::ops::DerefMut for $name {
+            fn deref_mut(&mut self) -> &mut Self::Target {
+                &mut self.0
+            }
+        }
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+    };
+}
+
+node_id!(ActivityNodeId);
+node_id!(MutexNodeId);
+node_id!(BarrierNodeId);
+node_id!(LockNodeId);
+node_id!(QueueNodeId);
+
+#[derive(PartialEq, Eq)]
+pub enum EditingMode {
+    None,
+    Delete,
+}
+
+impl Default for EditingMode {
+    fn default() -> Self {
+        Self::None
     }
 }
-impl std::ops::DerefMut for ActivityNodeId {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+
+#[derive(Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum TieBreaking {
+    Random,
+    LowestId,
+    RoundRobin,
+}
+
+impl Default for TieBreaking {
+    fn default() -> Self {
+        Self::Random
     }
 }
 
-#[derive(
-    PartialOrd, Ord, Default, Hash, Clone, Copy, Eq, PartialEq, serde::Serialize, serde::Deserialize,
-)]
-pub struct MutexNodeId(usize);
-impl std::ops::Deref for MutexNodeId {
-    type Target = usize;
-    fn deref(&self) -> &Self::Target {
-        &self.0
+impl TieBreaking {
+    pub const ALL: [Self; 3] = [Self::Random, Self::LowestId, Self::RoundRobin];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Random => "Random",
+            Self::LowestId => "Lowest ID",
+            Self::RoundRobin => "Round Robin",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|tie_breaking| tie_breaking.name().eq_ignore_ascii_case(name))
     }
 }
-impl std::ops::DerefMut for MutexNodeId {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+
+#[derive(Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum SchedulingPolicy {
+    FixedPriority,
+    EarliestDeadlineFirst,
+    RateMonotonic,
+    RoundRobin,
+}
+
+impl Default for SchedulingPolicy {
+    fn default() -> Self {
+        Self::FixedPriority
     }
 }
 
-#[derive(PartialEq, Eq)]
-pub enum EditingMode {
+impl SchedulingPolicy {
+    pub const ALL: [Self; 4] = [
+        Self::FixedPriority,
+        Self::EarliestDeadlineFirst,
+        Self::RateMonotonic,
+        Self::RoundRobin,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::FixedPriority => "Fixed Priority",
+            Self::EarliestDeadlineFirst => "Earliest Deadline First",
+            Self::RateMonotonic => "Rate Monotonic",
+            Self::RoundRobin => "Round Robin",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|policy| policy.name().eq_ignore_ascii_case(name))
+    }
+}
+
+// raises the priority of activities holding a mutex, to prevent priority inversion
+#[derive(Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum PriorityProtocol {
     None,
-    Delete,
+    // holders inherit the priority of the activities waiting for their mutexes
+    Inheritance,
+    // holders run at the highest priority of all activities using their mutexes
+    Ceiling,
 }
 
-impl Default for EditingMode {
+impl Default for PriorityProtocol {
     fn default() -> Self {
         Self::None
     }
 }
 
+impl PriorityProtocol {
+    pub const ALL: [Self; 3] = [Self::None, Self::Inheritance, Self::Ceiling];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::None => "None",
+            Self::Inheritance => "Inheritance",
+            Self::Ceiling => "Ceiling",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|protocol| protocol.name().eq_ignore_ascii_case(name))
+    }
+}
+
+pub struct DeadlineMiss {
+    pub tick: u64,
+    pub activity: ActivityNodeId,
+}
+
+pub struct Deadlock {
+    pub tick: u64,
+    pub blocked_activities: Vec<ActivityNodeId>,
+    pub blocking_mutexes: Vec<MutexNodeId>,
+    pub blocking_barriers: Vec<BarrierNodeId>,
+    pub blocking_queues: Vec<QueueNodeId>,
+}
+
 #[derive(Clone, Copy)]
 enum AnyNode {
     Activity(ActivityNodeId),
     Mutex(MutexNodeId),
+    Barrier(BarrierNodeId),
+    Lock(LockNodeId),
+    Queue(QueueNodeId),
+}
+
+// what the pointer did to the nodes in one frame
+#[derive(Default)]
+struct NodeInteractions {
+    left_clicked: Option<AnyNode>,
+    right_clicked: Option<AnyNode>,
+    moved: bool,
+}
+
+impl NodeInteractions {
+    // lets every node of one kind handle its clicks and drags
+    fn add<'a, Id: Copy + 'a, Node: 'a>(
+        &mut self,
+        nodes: impl Iterator<Item = (&'a Id, &'a mut Node)>,
+        kind: fn(Id) -> AnyNode,
+        pos: fn(&Node) -> Pos2,
+        mut interact: impl FnMut(&mut Node) -> Option<egui::Response>,
+    ) {
+        for (id, node) in nodes {
+            let previous_pos = pos(node);
+            if let Some(response) = interact(node) {
+                if response.clicked() {
+                    self.left_clicked = Some(kind(*id));
+                }
+                if response.secondary_clicked() {
+                    self.right_clicked = Some(kind(*id));
+                }
+            }
+            self.moved |= pos(node) != previous_pos;
+        }
+    }
 }
 
 #[derive(serde::Deserialize, serde::Serialize)]
+#[serde(default)]
 pub struct Graph {
     pub name: String,
     activity_nodes: indexmap::IndexMap<ActivityNodeId, ActivityNode>,
     mutex_nodes: std::collections::HashMap<MutexNodeId, MutexNode>,
+    barrier_nodes: std::collections::HashMap<BarrierNodeId, BarrierNode>,
+    lock_nodes: std::collections::HashMap<LockNodeId, LockNode>,
+    queue_nodes: std::collections::HashMap<QueueNodeId, QueueNode>,
 
     connections: std::collections::HashMap<
         ActivityNodeId,
@@ -73,6 +258,9 @@ pub struct Graph {
 
     next_activity_id: ActivityNodeId,
     next_mutex_id: MutexNodeId,
+    next_barrier_id: BarrierNodeId,
+    next_lock_id: LockNodeId,
+    next_queue_id: QueueNodeId,
 
     tick_progress: f32,
 
@@ -80,6 +268,19 @@ pub struct Graph {
 
     remaining_ticks_to_run: i32,
 
+    pub seed: u32,
+    pub tie_breaking: TieBreaking,
+    // None means every ready activity can run at the same time
+    pub core_count: Option<u32>,
+    pub preemptive: bool,
+    pub pause_on_deadline_miss: bool,
+    pub scheduling_policy: SchedulingPolicy,
+    // only used by fixed priority
+    pub priority_protocol: PriorityProtocol,
+    // ticks an activity may run before it has to make room, only used by round robin
+    pub quantum: u32,
+    current_tick: u64,
+
     #[serde(skip)]
     currently_connecting_from: Option<AnyNode>,
 
@@ -88,6 +289,28 @@ pub struct Graph {
 
     #[serde(skip)]
     autofit_rect: Option<egui::Rect>,
+
+    #[serde(skip)]
+    history: history::History,
+
+    #[serde(skip)]
+    timeline: timeline::Timeline,
+
+    #[serde(skip)]
+    deadlock: Option<Deadlock>,
+
+    // the first deadline miss since the last dismissal
+    #[serde(skip)]
+    deadline_miss: Option<DeadlineMiss>,
+
+    #[serde(skip)]
+    schedule: gantt::Schedule,
+
+    #[serde(skip)]
+    trace: trace::Trace,
+
+    #[serde(skip)]
+    statistics: statistics::Statistics,
 }
 
 impl Clone for Graph {
@@ -96,11 +319,26 @@ impl Clone for Graph {
             name: self.name.clone(),
             activity_nodes: self.activity_nodes.clone(),
             mutex_nodes: self.mutex_nodes.clone(),
+            barrier_nodes: self.barrier_nodes.clone(),
+            lock_nodes: self.lock_nodes.clone(),
+            queue_nodes: self.queue_nodes.clone(),
             connections: self.connections.clone(),
             next_activity_id: self.next_activity_id,
             next_mutex_id: self.next_mutex_id,
+            next_barrier_id: self.next_barrier_id,
+            next_lock_id: self.next_lock_id,
+            next_queue_id: self.next_queue_id,
             tick_progress: self.tick_progress,
             ticks_per_second: self.ticks_per_second,
+            seed: self.seed,
+            tie_breaking: self.tie_breaking,
+            core_count: self.core_count,
+            preemptive: self.preemptive,
+            pause_on_deadline_miss: self.pause_on_deadline_miss,
+            scheduling_policy: self.scheduling_policy,
+            priority_protocol: self.priority_protocol,
+            quantum: self.quantum,
+            current_tick: self.current_tick,
             ..Default::default()
         }
     }
@@ -112,24 +350,128 @@ impl Default for Graph {
             name: "Unnamed Graph".into(),
             activity_nodes: indexmap::IndexMap::new(),
             mutex_nodes: std::collections::HashMap::new(),
+            barrier_nodes: std::collections::HashMap::new(),
+            lock_nodes: std::collections::HashMap::new(),
+            queue_nodes: std::collections::HashMap::new(),
             connections: std::collections::HashMap::new(),
             next_activity_id: ActivityNodeId(0),
             next_mutex_id: MutexNodeId(0),
+            next_barrier_id: BarrierNodeId(0),
+            next_lock_id: LockNodeId(0),
+            next_queue_id: QueueNodeId(0),
             tick_progress: 0.,
             ticks_per_second: 1.,
             remaining_ticks_to_run: 0,
+            seed: 0,
+            tie_breaking: TieBreaking::Random,
+            core_count: None,
+            preemptive: false,
+            pause_on_deadline_miss: false,
+            scheduling_policy: SchedulingPolicy::FixedPriority,
+            priority_protocol: PriorityProtocol::None,
+            quantum: 1,
+            current_tick: 0,
             currently_connecting_from: None,
             editing_mode: EditingMode::None,
             autofit_rect: Some(egui::Rect::NAN),
+            history: history::History::default(),
+            timeline: timeline::Timeline::default(),
+            deadlock: None,
+            deadline_miss: None,
+            schedule: gantt::Schedule::default(),
+            trace: trace::Trace::default(),
+            statistics: statistics::Statistics::default(),
         }
     }
 }
 
+// deterministic iteration order, so that traces are reproducible
+fn sorted_by_mutex_id(
+    activity_connections: &std::collections::HashMap<MutexNodeId, connection::Connection>,
+) -> Vec<(&MutexNodeId, &connection::Connection)> {
+    let mut activity_connections = activity_connections.iter().collect::<Vec<_>>();
+    activity_connections.sort_by_key(|(mutex_id, _)| **mutex_id);
+    activity_connections
+}
+
+// "id" or "id:weight", as used in the connection lists of the csv format
+// weights are limited like in the task properties
+fn parse_connection_entry(entry: &str) -> Option<(usize, u32)> {
+    match entry.split_once(':') {
+        Some((id, weight)) => Some((
+            id.trim().parse::<usize>().ok()?,
+            weight
+                .trim()
+                .parse::<u32>()
+                .ok()
+                .filter(|weight| *weight > 0)?
+                .min(MAX_THREE_DIGIT_NUMBER),
+        )),
+        None => Some((entry.parse::<usize>().ok()?, 1)),
+    }
+}
+
+fn connection_entry(id: usize, weight: u32) -> String {
+    match weight {
+        1 => id.to_string(),
+        weight => format!("{}:{}", id, weight),
+    }
+}
+
+// inputs that do not simply consume are marked by a prefix, e.g. "!3" or "?3:2"
+const INPUT_KIND_PREFIXES: [(connection::InputKind, char); 2] = [
+    (connection::InputKind::Inhibitor, '!'),
+    (connection::InputKind::Read, '?'),
+];
+
+fn parse_input_entry(entry: &str) -> Option<(connection::InputKind, usize, u32)> {
+    let (input_kind, entry) = INPUT_KIND_PREFIXES
+        .into_iter()
+        .find_map(|(input_kind, prefix)| Some((input_kind, entry.strip_prefix(prefix)?)))
+        .unwrap_or((connection::InputKind::Consume, entry));
+    let (id, weight) = parse_connection_entry(entry)?;
+    Some((input_kind, id, weight))
+}
+
+// outputs that do not simply produce are marked by a prefix, e.g. "~3"
+const OUTPUT_KIND_PREFIXES: [(connection::OutputKind, char); 1] =
+    [(connection::OutputKind::Reset, '~')];
+
+fn parse_output_entry(entry: &str) -> Option<(connection::OutputKind, usize, u32)> {
+    let (output_kind, entry) = OUTPUT_KIND_PREFIXES
+        .into_iter()
+        .find_map(|(output_kind, prefix)| Some((output_kind, entry.strip_prefix(prefix)?)))
+        .unwrap_or((connection::OutputKind::Produce, entry));
+    let (id, weight) = parse_connection_entry(entry)?;
+    Some((output_kind, id, weight))
+}
+
+fn output_entry(id: usize, connection: &connection::Connection) -> String {
+    let prefix = OUTPUT_KIND_PREFIXES
+        .into_iter()
+        .find(|(output_kind, _)| *output_kind == connection.get_output_kind())
+        .map(|(_, prefix)| prefix.to_string())
+        .unwrap_or_default();
+    prefix + &connection_entry(id, connection.get_output_weight())
+}
+
+fn input_entry(id: usize, connection: &connection::Connection) -> String {
+    let prefix = INPUT_KIND_PREFIXES
+        .into_iter()
+        .find(|(input_kind, _)| *input_kind == connection.get_input_kind())
+        .map(|(_, prefix)| prefix.to_string())
+        .unwrap_or_default();
+    prefix + &connection_entry(id, connection.get_input_weight())
+}
+
 // import/export
 impl Graph {
     pub fn from_csv(text: &str) -> Result<Self, String> {
         const SEPERATOR: char = ';';
         let mut graph = Graph::default();
+        // applied after all tasks and mutexes are known
+        let mut task_settings = Vec::new();
+        let mut mutex_settings = Vec::new();
 
         for (line_number, line) in text.lines().enumerate() {
             let line_number = line_number + 1; // enumerate starts at 0
@@ -171,17 +513,23 @@ impl Graph {
                     values[9..]
                         .iter()
                         .filter(|x| !x.is_empty())
-                        .find_map(|x| match x.parse::<usize>() {
-                            Ok(mutex_id) => {
+                        .find_map(|x| match parse_output_entry(x) {
+                            Some((output_kind, mutex_id, weight)) => {
                                 graph.connect(
                                     activity_id,
                                     MutexNodeId(mutex_id),
                                     Direction::ActivityToMutex,
                                     false,
                                 );
+                                if let Some(connection) =
+                                    graph.get_connection_mut(activity_id, MutexNodeId(mutex_id))
+                                {
+                                    connection.set_output_weight(weight);
+                                    connection.set_output_kind(output_kind);
+                                }
                                 None
                             }
-                            Err(_) => Some(Err(format!(
+                            None => Some(Err(format!(
                                 "Error while parsing Activity Connection in line: {}",
                                 line_number
                             ))),
@@ -210,26 +558,347 @@ impl Graph {
                     values[5..]
                         .iter()
                         .filter(|x| !x.is_empty())
-                        .find_map(|x| match x.parse::<usize>() {
-                            Ok(activity_id) => {
+                        .find_map(|x| match parse_input_entry(x) {
+                            Some((input_kind, activity_id, weight)) => {
                                 graph.connect(
                                     ActivityNodeId(activity_id),
                                     mutex_id,
                                     Direction::MutexToActivity,
                                     false,
                                 );
+                                if let Some(connection) =
+                                    graph.get_connection_mut(ActivityNodeId(activity_id), mutex_id)
+                                {
+                                    connection.set_input_weight(weight);
+                                    connection.set_input_kind(input_kind);
+                                }
                                 None
                             }
-                            Err(_) => Some(Err(format!(
+                            None => Some(Err(format!(
                                 "Error while parsing Activity Connection in line: {}",
                                 line_number
                             ))),
                         })
                         .unwrap_or(Ok(()))?;
                 }
+
+                "barrier" if values.len() >= 6 => {
+                    let barrier_node = BarrierNode::new(egui::Pos2 {
+                        x: values[1].parse::<f32>().map_err(|_| {
+                            format!("Error while parsing Position X in line: {}", line_number)
+                        })?,
+                        y: values[2].parse::<f32>().map_err(|_| {
+                            format!("Error while parsing Position Y in line: {}", line_number)
+                        })?,
+                    });
+                    let barrier_id =
+                        BarrierNodeId(values[3].parse::<usize>().map_err(|_| {
+                            format!("Error while parsing ID in line: {}", line_number)
+                        })?);
+                    let count = values[4]
+                        .parse::<u32>()
+                        .ok()
+                        .filter(|count| *count > 0)
+                        .ok_or_else(|| {
+                            format!("Error while parsing Count in line: {}", line_number)
+                        })?;
+                    let arrived = values[5]
+                        .split_whitespace()
+                        .map(|activity_id| activity_id.parse::<usize>().map(ActivityNodeId))
+                        .collect::<Result<std::collections::BTreeSet<_>, _>>()
+                        .map_err(|_| {
+                            format!("Error while parsing Arrivals in line: {}", line_number)
+                        })?;
+                    graph.add_barrier_node_with_id(barrier_node, barrier_id);
+
+                    values[6..]
+                        .iter()
+                        .filter(|x| !x.is_empty())
+                        .find_map(|x| {
+                            let (arriving, activity_id) = match x.strip_prefix('+') {
+                                Some(activity_id) => (true, activity_id),
+                                None => (false, *x),
+                            };
+                            match activity_id.parse::<usize>() {
+                                Ok(activity_id) => {
+                                    graph.toggle_barrier_connection(
+                                        ActivityNodeId(activity_id),
+                                        barrier_id,
+                                        arriving,
+                                    );
+                                    None
+                                }
+                                Err(_) => Some(Err(format!(
+                                    "Error while parsing Barrier Connection in line: {}",
+                                    line_number
+                                ))),
+                            }
+                        })
+                        .unwrap_or(Ok(()))?;
+
+                    // set after the connections, which make the count follow the arriving tasks
+                    if let Some(barrier_node) = graph.barrier_nodes.get_mut(&barrier_id) {
+                        if count > barrier_node.arriving.len().max(1) as u32 {
+                            return Err(format!(
+                                "Count exceeds the number of arriving tasks in line: {}",
+                                line_number
+                            ));
+                        }
+                        if !arrived.is_subset(&barrier_node.arriving) {
+                            return Err(format!(
+                                "Error while parsing Arrivals in line: {}",
+                                line_number
+                            ));
+                        }
+                        barrier_node.count = count;
+                        barrier_node.arrived = arrived;
+                    }
+                }
+
+                "lock" if values.len() >= 4 => {
+                    let lock_node = LockNode::new(egui::Pos2 {
+                        x: values[1].parse::<f32>().map_err(|_| {
+                            format!("Error while parsing Position X in line: {}", line_number)
+                        })?,
+                        y: values[2].parse::<f32>().map_err(|_| {
+                            format!("Error while parsing Position Y in line: {}", line_number)
+                        })?,
+                    });
+                    let lock_id =
+                        LockNodeId(values[3].parse::<usize>().map_err(|_| {
+                            format!("Error while parsing ID in line: {}", line_number)
+                        })?);
+                    graph.add_lock_node_with_id(lock_node, lock_id);
+
+                    values[4..]
+                        .iter()
+                        .filter(|x| !x.is_empty())
+                        .find_map(|x| {
+                            let (access, activity_id) = match x.strip_prefix('?') {
+                                Some(activity_id) => (LockAccess::Shared, activity_id),
+                                None => (LockAccess::Exclusive, *x),
+                            };
+                            match activity_id.parse::<usize>() {
+                                Ok(activity_id) => {
+                                    if let Some(lock_node) = graph.lock_nodes.get_mut(&lock_id) {
+                                        lock_node
+                                            .access
+                                            .insert(ActivityNodeId(activity_id), access);
+                                    }
+                                    None
+                                }
+                                Err(_) => Some(Err(format!(
+                                    "Error while parsing Lock Connection in line: {}",
+                                    line_number
+                                ))),
+                            }
+                        })
+                        .unwrap_or(Ok(()))?;
+                }
+
+                "queue" if values.len() >= 5 => {
+                    let mut queue_node = QueueNode::new(egui::Pos2 {
+                        x: values[1].parse::<f32>().map_err(|_| {
+                            format!("Error while parsing Position X in line: {}", line_number)
+                        })?,
+                        y: values[2].parse::<f32>().map_err(|_| {
+                            format!("Error while parsing Position Y in line: {}", line_number)
+                        })?,
+                    });
+                    let queue_id =
+                        QueueNodeId(values[3].parse::<usize>().map_err(|_| {
+                            format!("Error while parsing ID in line: {}", line_number)
+                        })?);
+                    queue_node.tokens = values[4]
+                        .split_whitespace()
+                        .map(queue_node::sanitize_label)
+                        .collect();
+                    graph.add_queue_node_with_id(queue_node, queue_id);
+
+                    values[5..]
+                        .iter()
+                        .filter(|x| !x.is_empty())
+                        .find_map(|x| {
+                            let (producing, entry) = match x.strip_prefix('+') {
+                                Some(entry) => (true, entry),
+                                None => (false, *x),
+                            };
+                            let (activity_id, label) = match entry.split_once('=') {
+                                Some((activity_id, label)) => (activity_id, Some(label)),
+                                None => (entry, None),
+                            };
+                            match activity_id.parse::<usize>() {
+                                Ok(activity_id) => {
+                                    let activity_id = ActivityNodeId(activity_id);
+                                    graph.toggle_queue_connection(activity_id, queue_id, producing);
+                                    if let (true, Some(label), Some(queue_node)) =
+                                        (producing, label, graph.queue_nodes.get_mut(&queue_id))
+                                    {
+                                        queue_node
+                                            .producers
+                                            .insert(activity_id, queue_node::sanitize_label(label));
+                                    }
+                                    None
+                                }
+                                Err(_) => Some(Err(format!(
+                                    "Error while parsing Queue Connection in line: {}",
+                                    line_number
+                                ))),
+                            }
+                        })
+                        .unwrap_or(Ok(()))?;
+                }
+
+                "setting" if values.len() >= 3 => match values[1].to_lowercase().as_str() {
+                    "seed" => {
+                        graph.seed = values[2].parse::<u32>().map_err(|_| {
+                            format!("Error while parsing Seed in line: {}", line_number)
+                        })?;
+                    }
+                    "tie breaking" => {
+                        graph.tie_breaking =
+                            TieBreaking::from_name(values[2]).ok_or_else(|| {
+                                format!("Error while parsing Tie Breaking in line: {}", line_number)
+                            })?;
+                    }
+                    "cores" => {
+                        graph.core_count = Some(
+                            values[2]
+                                .parse::<u32>()
+                                .ok()
+                                .filter(|cores| *cores > 0)
+                                .ok_or_else(|| {
+                                    format!("Error while parsing Cores in line: {}", line_number)
+                                })?,
+                        );
+                    }
+                    "scheduling policy" => {
+                        graph.scheduling_policy = SchedulingPolicy::from_name(values[2])
+                            .ok_or_else(|| {
+                                format!(
+                                    "Error while parsing Scheduling Policy in line: {}",
+                                    line_number
+                                )
+                            })?;
+                    }
+                    "priority protocol" => {
+                        graph.priority_protocol = PriorityProtocol::from_name(values[2])
+                            .ok_or_else(|| {
+                                format!(
+                                    "Error while parsing Priority Protocol in line: {}",
+                                    line_number
+                                )
+                            })?;
+                    }
+                    "quantum" => {
+                        graph.quantum = values[2]
+                            .parse::<u32>()
+                            .ok()
+                            .filter(|quantum| *quantum > 0)
+                            .ok_or_else(|| {
+                                format!("Error while parsing Quantum in line: {}", line_number)
+                            })?;
+                    }
+                    "preemptive" => {
+                        graph.preemptive =
+                            values[2].to_lowercase().parse::<bool>().map_err(|_| {
+                                format!("Error while parsing Preemptive in line: {}", line_number)
+                            })?;
+                    }
+                    "pause on deadline miss" => {
+                        graph.pause_on_deadline_miss =
+                            values[2].to_lowercase().parse::<bool>().map_err(|_| {
+                                format!(
+                                    "Error while parsing Pause On Deadline Miss in line: {}",
+                                    line_number
+                                )
+                            })?;
+                    }
+                    _ => {} // skip unknown setting
+                },
+
+                "tasksetting" if values.len() >= 4 => {
+                    let activity_id =
+                        ActivityNodeId(values[1].parse::<usize>().map_err(|_| {
+                            format!("Error while parsing ID in line: {}", line_number)
+                        })?);
+                    task_settings.push((line_number, activity_id, values[2], values[3]));
+                }
+
+                "mutexsetting" if values.len() >= 4 => {
+                    let mutex_id =
+                        MutexNodeId(values[1].parse::<usize>().map_err(|_| {
+                            format!("Error while parsing ID in line: {}", line_number)
+                        })?);
+                    mutex_settings.push((line_number, mutex_id, values[2], values[3]));
+                }
                 _ => {} // skip line
             }
         }
+
+        for (line_number, activity_id, name, value) in task_settings {
+            let activity_node = graph
+                .activity_nodes
+                .get_mut(&activity_id)
+                .ok_or_else(|| format!("Unknown Task ID in line: {}", line_number))?;
+            match name.to_lowercase().as_str() {
+                "period" => {
+                    activity_node.period = value.parse::<u32>().map_err(|_| {
+                        format!("Error while parsing Period in line: {}", line_number)
+                    })?;
+                }
+                "duration distribution" => {
+                    activity_node.duration_distribution =
+                        distribution::DurationDistribution::from_text(value).ok_or_else(|| {
+                            format!(
+                                "Error while parsing Duration Distribution in line: {}",
+                                line_number
+                            )
+                        })?;
+                }
+                "phase" => {
+                    activity_node.phase = value.parse::<u32>().map_err(|_| {
+                        format!("Error while parsing Phase in line: {}", line_number)
+                    })?;
+                }
+                "deadline" => {
+                    activity_node.deadline = value.parse::<u32>().map_err(|_| {
+                        format!("Error while parsing Deadline in line: {}", line_number)
+                    })?;
+                }
+                _ => {} // skip unknown setting
+            }
+        }
+        for (line_number, mutex_id, name, value) in mutex_settings {
+            let mutex_node = graph
+                .mutex_nodes
+                .get_mut(&mutex_id)
+                .ok_or_else(|| format!("Unknown Mutex ID in line: {}", line_number))?;
+            match name.to_lowercase().as_str() {
+                "capacity" => {
+                    mutex_node.capacity = Some(
+                        value
+                            .parse::<u32>()
+                            .ok()
+                            .filter(|capacity| *capacity > 0)
+                            .ok_or_else(|| {
+                                format!("Error while parsing Capacity in line: {}", line_number)
+                            })?,
+                    );
+                }
+                "period" => {
+                    mutex_node.period = value.parse::<u32>().map_err(|_| {
+                        format!("Error while parsing Period in line: {}", line_number)
+                    })?;
+                }
+                "phase" => {
+                    mutex_node.phase = value.parse::<u32>().map_err(|_| {
+                        format!("Error while parsing Phase in line: {}", line_number)
+                    })?;
+                }
+                _ => {} // skip unknown setting
+            }
+        }
         graph.update_connection_states();
         Ok(graph)
     }
@@ -238,8 +907,8 @@ impl Graph {
         use std::collections::HashMap;
         let seperator = ";";
 
-        let mut connection_activity_to_mutex: HashMap<usize, Vec<usize>> = HashMap::new();
-        let mut connection_mutex_to_activity: HashMap<usize, Vec<usize>> = HashMap::new();
+        let mut connection_activity_to_mutex: HashMap<usize, Vec<String>> = HashMap::new();
+        let mut connection_mutex_to_activity: HashMap<usize, Vec<String>> = HashMap::new();
 
         for (activity_id, activity_connections) in &self.connections {
             for (mutex_id, connection) in activity_connections {
@@ -248,23 +917,23 @@ impl Graph {
                         connection_activity_to_mutex
                             .entry(activity_id.0)
                             .or_default()
-                            .push(mutex_id.0);
+                            .push(output_entry(mutex_id.0, connection));
                     }
                     Direction::MutexToActivity => {
                         connection_mutex_to_activity
                             .entry(mutex_id.0)
                             .or_default()
-                            .push(activity_id.0);
+                            .push(input_entry(activity_id.0, connection));
                     }
                     Direction::TwoWay => {
                         connection_activity_to_mutex
                             .entry(activity_id.0)
                             .or_default()
-                            .push(mutex_id.0);
+                            .push(output_entry(mutex_id.0, connection));
                         connection_mutex_to_activity
                             .entry(mutex_id.0)
                             .or_default()
-                            .push(activity_id.0);
+                            .push(input_entry(activity_id.0, connection));
                     }
                 }
             }
@@ -288,9 +957,8 @@ impl Graph {
                 activity_node.remaining_duration,
                 connection_activity_to_mutex
                     .get(&activity_id.0)
-                    .map(|x| x.iter().map(|x| x.to_string()).collect::<Vec<String>>())
+                    .map(|x| x.join(seperator))
                     .unwrap_or_default()
-                    .join(seperator)
             ));
         }
 
@@ -305,9 +973,167 @@ impl Graph {
                 mutex_node.value,
                 connection_mutex_to_activity
                     .get(&mutex_id.0)
-                    .map(|x| x.iter().map(|x| x.to_string()).collect::<Vec<String>>())
+                    .map(|x| x.join(seperator))
                     .unwrap_or_default()
-                    .join(seperator)
+            ));
+        }
+
+        // add barriers, arriving tasks are marked with a leading +
+        csv.push_str("#Barrier;Position X;Position Y;ID;Count;Arrivals (space seperated list of arrived Task IDs);[Semicolon seperated list of connected Task IDs]\n");
+        for (barrier_id, barrier_node) in &self.barrier_nodes {
+            let activity_entries = barrier_node
+                .arriving
+                .iter()
+                .map(|activity_id| format!("+{}", activity_id.0))
+                .chain(
+                    barrier_node
+                        .waiting
+                        .iter()
+                        .map(|activity_id| activity_id.0.to_string()),
+                )
+                .collect::<Vec<_>>();
+            csv.push_str(&format!(
+                "Barrier{seperator}{}{seperator}{}{seperator}{}{seperator}{}{seperator}{}{seperator}{}\n",
+                barrier_node.pos.x.round() as i64,
+                barrier_node.pos.y.round() as i64,
+                barrier_id.0,
+                barrier_node.count,
+                barrier_node
+                    .arrived
+                    .iter()
+                    .map(|activity_id| activity_id.0.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                activity_entries.join(seperator)
+            ));
+        }
+
+        // add locks, shared access is marked with a leading ?
+        csv.push_str(
+            "#Lock;Position X;Position Y;ID;[Semicolon seperated list of connected Task IDs]\n",
+        );
+        for (lock_id, lock_node) in &self.lock_nodes {
+            let activity_entries = lock_node
+                .access
+                .iter()
+                .map(|(activity_id, access)| match access {
+                    LockAccess::Shared => format!("?{}", activity_id.0),
+                    LockAccess::Exclusive => activity_id.0.to_string(),
+                })
+                .collect::<Vec<_>>();
+            csv.push_str(&format!(
+                "Lock{seperator}{}{seperator}{}{seperator}{}{seperator}{}\n",
+                lock_node.pos.x.round() as i64,
+                lock_node.pos.y.round() as i64,
+                lock_id.0,
+                activity_entries.join(seperator)
+            ));
+        }
+
+        // add queues, producers are marked with a leading + and followed by their label
+        csv.push_str("#Queue;Position X;Position Y;ID;Space seperated list of Tokens;[Semicolon seperated list of connected Task IDs]\n");
+        for (queue_id, queue_node) in &self.queue_nodes {
+            let activity_entries = queue_node
+                .producers
+                .iter()
+                .map(|(activity_id, label)| format!("+{}={}", activity_id.0, label))
+                .chain(
+                    queue_node
+                        .consumers
+                        .iter()
+                        .map(|activity_id| activity_id.0.to_string()),
+                )
+                .collect::<Vec<_>>();
+            csv.push_str(&format!(
+                "Queue{seperator}{}{seperator}{}{seperator}{}{seperator}{}{seperator}{}\n",
+                queue_node.pos.x.round() as i64,
+                queue_node.pos.y.round() as i64,
+                queue_id.0,
+                queue_node
+                    .tokens
+                    .iter()
+                    .cloned()
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                activity_entries.join(seperator)
+            ));
+        }
+
+        // add task settings, only if they differ from the default
+        csv.push_str("#TaskSetting;ID;Name;Value\n");
+        for (activity_id, activity_node) in &self.activity_nodes {
+            for (name, value) in [
+                ("Period", activity_node.period),
+                ("Phase", activity_node.phase),
+                ("Deadline", activity_node.deadline),
+            ] {
+                if value != 0 {
+                    csv.push_str(&format!(
+                        "TaskSetting{seperator}{}{seperator}{}{seperator}{}\n",
+                        activity_id.0, name, value
+                    ));
+                }
+            }
+            if let Some(distribution) = activity_node.duration_distribution.to_text() {
+                csv.push_str(&format!(
+                    "TaskSetting{seperator}{}{seperator}Duration Distribution{seperator}{}\n",
+                    activity_id.0, distribution
+                ));
+            }
+        }
+
+        // add mutex settings, only if they differ from the default
+        csv.push_str("#MutexSetting;ID;Name;Value\n");
+        for (mutex_id, mutex_node) in &self.mutex_nodes {
+            if let Some(capacity) = mutex_node.capacity {
+                csv.push_str(&format!(
+                    "MutexSetting{seperator}{}{seperator}Capacity{seperator}{}\n",
+                    mutex_id.0, capacity
+                ));
+            }
+            for (name, value) in [("Period", mutex_node.period), ("Phase", mutex_node.phase)] {
+                if value != 0 {
+                    csv.push_str(&format!(
+                        "MutexSetting{seperator}{}{seperator}{}{seperator}{}\n",
+                        mutex_id.0, name, value
+                    ));
+                }
+            }
+        }
+
+        // add settings
+        csv.push_str("#Setting;Name;Value\n");
+        csv.push_str(&format!("Setting{seperator}Seed{seperator}{}\n", self.seed));
+        csv.push_str(&format!(
+            "Setting{seperator}Tie Breaking{seperator}{}\n",
+            self.tie_breaking.name()
+        ));
+        if let Some(core_count) = self.core_count {
+            csv.push_str(&format!(
+                "Setting{seperator}Cores{seperator}{}\n",
+                core_count
+            ));
+        }
+        csv.push_str(&format!(
+            "Setting{seperator}Scheduling Policy{seperator}{}\n",
+            self.scheduling_policy.name()
+        ));
+        if self.priority_protocol != PriorityProtocol::None {
+            csv.push_str(&format!(
+                "Setting{seperator}Priority Protocol{seperator}{}\n",
+                self.priority_protocol.name()
+            ));
+        }
+        csv.push_str(&format!(
+            "Setting{seperator}Quantum{seperator}{}\n",
+            self.quantum
+        ));
+        if self.preemptive {
+            csv.push_str(&format!("Setting{seperator}Preemptive{seperator}true\n"));
+        }
+        if self.pause_on_deadline_miss {
+            csv.push_str(&format!(
+                "Setting{seperator}Pause On Deadline Miss{seperator}true\n"
             ));
         }
 
@@ -317,6 +1143,46 @@ impl Graph {
 
 // structure
 impl Graph {
+    pub fn activity_nodes(&self) -> impl Iterator<Item = (ActivityNodeId, &ActivityNode)> {
+        self.activity_nodes.iter().map(|(id, node)| (*id, node))
+    }
+
+    pub fn mutex_nodes(&self) -> impl Iterator<Item = (MutexNodeId, &MutexNode)> {
+        self.mutex_nodes.iter().map(|(id, node)| (*id, node))
+    }
+
+    pub fn get_activity_node(&self, id: ActivityNodeId) -> Option<&ActivityNode> {
+        self.activity_nodes.get(&id)
+    }
+
+    pub fn get_mutex_node(&self, id: MutexNodeId) -> Option<&MutexNode> {
+        self.mutex_nodes.get(&id)
+    }
+
+    pub fn barrier_nodes(&self) -> impl Iterator<Item = (BarrierNodeId, &BarrierNode)> {
+        self.barrier_nodes.iter().map(|(id, node)| (*id, node))
+    }
+
+    pub fn get_barrier_node(&self, id: BarrierNodeId) -> Option<&BarrierNode> {
+        self.barrier_nodes.get(&id)
+    }
+
+    pub fn lock_nodes(&self) -> impl Iterator<Item = (LockNodeId, &LockNode)> {
+        self.lock_nodes.iter().map(|(id, node)| (*id, node))
+    }
+
+    pub fn get_lock_node(&self, id: LockNodeId) -> Option<&LockNode> {
+        self.lock_nodes.get(&id)
+    }
+
+    pub fn queue_nodes(&self) -> impl Iterator<Item = (QueueNodeId, &QueueNode)> {
+        self.queue_nodes.iter().map(|(id, node)| (*id, node))
+    }
+
+    pub fn get_queue_node(&self, id: QueueNodeId) -> Option<&QueueNode> {
+        self.queue_nodes.get(&id)
+    }
+
     pub fn add_activity_node(&mut self, activity_node: ActivityNode) -> ActivityNodeId {
         self.add_activiy_node_with_id(activity_node, self.next_activity_id)
     }
@@ -343,9 +1209,86 @@ impl Graph {
         id
     }
 
-    pub fn connect(
+    pub fn add_barrier_node(&mut self, barrier_node: BarrierNode) -> BarrierNodeId {
+        self.add_barrier_node_with_id(barrier_node, self.next_barrier_id)
+    }
+    pub fn add_barrier_node_with_id(
         &mut self,
-        activity_id: ActivityNodeId,
+        barrier_node: BarrierNode,
+        id: BarrierNodeId,
+    ) -> BarrierNodeId {
+        self.barrier_nodes.insert(id, barrier_node);
+        *self.next_barrier_id = usize::max(*self.next_barrier_id, *id + 1);
+        id
+    }
+
+    pub fn add_lock_node(&mut self, lock_node: LockNode) -> LockNodeId {
+        self.add_lock_node_with_id(lock_node, self.next_lock_id)
+    }
+    pub fn add_lock_node_with_id(&mut self, lock_node: LockNode, id: LockNodeId) -> LockNodeId {
+        self.lock_nodes.insert(id, lock_node);
+        *self.next_lock_id = usize::max(*self.next_lock_id, *id + 1);
+        id
+    }
+
+    pub fn add_queue_node(&mut self, queue_node: QueueNode) -> QueueNodeId {
+        self.add_queue_node_with_id(queue_node, self.next_queue_id)
+    }
+    pub fn add_queue_node_with_id(
+        &mut self,
+        queue_node: QueueNode,
+        id: QueueNodeId,
+    ) -> QueueNodeId {
+        self.queue_nodes.insert(id, queue_node);
+        *self.next_queue_id = usize::max(*self.next_queue_id, *id + 1);
+        id
+    }
+
+    // producers push their label when they finish, consumers pop a token when they start
+    pub fn toggle_queue_connection(
+        &mut self,
+        activity_id: ActivityNodeId,
+        queue_id: QueueNodeId,
+        producing: bool,
+    ) {
+        if let Some(queue_node) = self.queue_nodes.get_mut(&queue_id) {
+            match producing {
+                true => queue_node.toggle_producer(activity_id),
+                false => queue_node.toggle_consumer(activity_id),
+            }
+        }
+    }
+
+    // new connections are exclusive, the access can be changed in the task properties
+    pub fn toggle_lock_connection(&mut self, activity_id: ActivityNodeId, lock_id: LockNodeId) {
+        if let Some(lock_node) = self.lock_nodes.get_mut(&lock_id) {
+            if lock_node.access.remove(&activity_id).is_none() {
+                lock_node.access.insert(activity_id, LockAccess::Exclusive);
+            }
+        }
+    }
+
+    // arriving activities count towards the barrier when they finish, the others wait for it
+    pub fn toggle_barrier_connection(
+        &mut self,
+        activity_id: ActivityNodeId,
+        barrier_id: BarrierNodeId,
+        arriving: bool,
+    ) {
+        if let Some(barrier_node) = self.barrier_nodes.get_mut(&barrier_id) {
+            if arriving {
+                barrier_node.toggle_arriving(activity_id);
+            } else if !barrier_node.waiting.remove(&activity_id) {
+                barrier_node.waiting.insert(activity_id);
+            } else {
+                barrier_node.passes.remove(&activity_id);
+            }
+        }
+    }
+
+    pub fn connect(
+        &mut self,
+        activity_id: ActivityNodeId,
         mutex_id: MutexNodeId,
         direction: Direction,
         update_connections: bool,
@@ -447,6 +1390,16 @@ impl Graph {
             .unwrap_or(false)
     }
 
+    pub fn get_connection_mut(
+        &mut self,
+        activity_id: ActivityNodeId,
+        mutex_id: MutexNodeId,
+    ) -> Option<&mut connection::Connection> {
+        self.connections
+            .get_mut(&activity_id)
+            .and_then(|activity_connections| activity_connections.get_mut(&mutex_id))
+    }
+
     pub fn toggle_connection(
         &mut self,
         activity_id: ActivityNodeId,
@@ -503,125 +1456,1067 @@ impl Graph {
 impl Graph {
     pub fn tick(&mut self, ui: &egui::Ui) {
         if self.remaining_ticks_to_run != 0 {
-            let mut previous_tick_progress = self.tick_progress;
-            self.tick_progress += ui.ctx().input(|i| i.stable_dt) * self.ticks_per_second;
             ui.ctx().request_repaint(); // keep the simulation running
-            loop {
-                if previous_tick_progress < 0.5 && self.tick_progress >= 0.5 {
-                    self.tick_a();
-                    self.do_per_connection(|c, a, m| {
-                        c.tick(a, m);
-                    });
+            self.advance(ui.ctx().input(|i| i.stable_dt));
+        }
+    }
+
+    /// Advances the animated simulation by `seconds`, executing ticks as they become due.
+    pub fn advance(&mut self, seconds: f32) {
+        if self.remaining_ticks_to_run == 0 {
+            return;
+        }
+
+        let mut previous_tick_progress = self.tick_progress;
+        self.tick_progress += seconds * self.ticks_per_second;
+        loop {
+            if previous_tick_progress < 0.5 && self.tick_progress >= 0.5 {
+                self.half_tick_a();
+                if self.pauses_after(self.current_tick) {
+                    // pause after finishing the current tick
+                    self.remaining_ticks_to_run = 1;
+                }
+            }
+            if self.tick_progress >= 1. {
+                self.half_tick_b();
+
+                self.tick_progress -= 1.;
+                if self.remaining_ticks_to_run > 0 {
+                    self.remaining_ticks_to_run -= 1;
+                    if self.remaining_ticks_to_run == 0 {
+                        self.tick_progress = 0.;
+                    }
                 }
-                if self.tick_progress >= 1. {
-                    self.tick_b();
-                    self.do_per_connection(|c, a, m| {
-                        c.tick(a, m);
-                    });
 
-                    self.tick_progress -= 1.;
-                    if self.remaining_ticks_to_run > 0 {
-                        self.remaining_ticks_to_run -= 1;
-                        if self.remaining_ticks_to_run == 0 {
-                            self.tick_progress = 0.;
-                        }
+                // make sure tick_a() is called
+                previous_tick_progress = 0.;
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Executes one full tick immediately, independent of any animation.
+    /// A partially animated tick is completed instead.
+    pub fn step(&mut self) {
+        if self.tick_progress < 0.5 {
+            self.half_tick_a();
+        }
+        self.half_tick_b();
+        self.tick_progress = 0.;
+    }
+
+    /// Executes up to `ticks` full ticks immediately, stopping early on a deadlock.
+    /// Returns the number of executed ticks.
+    pub fn run(&mut self, ticks: u32) -> u32 {
+        self.run_with(ticks, |_| Ok::<(), std::convert::Infallible>(()))
+            .unwrap_or_else(|never| match never {})
+    }
+
+    /// Like [`Graph::run`], but calls `after_tick` after every executed tick.
+    /// An error from `after_tick` stops the run and is returned.
+    pub fn run_with<E>(
+        &mut self,
+        ticks: u32,
+        mut after_tick: impl FnMut(&Self) -> Result<(), E>,
+    ) -> Result<u32, E> {
+        for tick in 0..ticks {
+            let current_tick = self.current_tick;
+            self.step();
+            after_tick(self)?;
+            if self.pauses_after(current_tick) {
+                return Ok(tick + 1);
+            }
+        }
+        Ok(ticks)
+    }
+
+    // only a deadline miss detected in the given tick pauses the simulation,
+    // older ones are still shown until they are dismissed
+    fn pauses_after(&self, tick: u64) -> bool {
+        self.deadlock.is_some()
+            || (self.pause_on_deadline_miss
+                && self
+                    .deadline_miss
+                    .as_ref()
+                    .map_or(false, |deadline_miss| deadline_miss.tick == tick))
+    }
+
+    fn half_tick_a(&mut self) {
+        self.timeline.record(self.simulation_snapshot());
+        self.trace.discard_from(self.current_tick);
+        self.tick_a();
+        self.deadlock = self.detect_deadlock();
+        let statuses = self
+            .activity_nodes
+            .iter()
+            .map(|(id, activity_node)| {
+                let status = match (activity_node.remaining_duration, activity_node.preempted) {
+                    // activities without a period are ready whenever they are not running,
+                    // so they wait even if it is a mutex, barrier, lock or queue that blocks them
+                    (0, _)
+                        if activity_node.has_unfinished_run() || !activity_node.is_periodic() =>
+                    {
+                        gantt::ActivityStatus::Waiting
                     }
+                    (0, _) => gantt::ActivityStatus::Idle,
+                    (_, true) => gantt::ActivityStatus::Preempted,
+                    (_, false) => gantt::ActivityStatus::Running,
+                };
+                (*id, status)
+            })
+            .collect::<Vec<_>>();
+        self.statistics.ticks += 1;
+        for (id, status) in &statuses {
+            self.statistics.record_activity(*id, *status);
+        }
+        self.schedule.record(gantt::TickRecord {
+            tick: self.current_tick,
+            statuses,
+        });
+        self.record_mutex_statistics();
+        self.do_per_connection(|c, a, m| {
+            c.tick(a, m);
+        });
+    }
 
-                    // make sure tick_a() is called
-                    previous_tick_progress = 0.;
-                } else {
-                    break;
-                }
+    fn half_tick_b(&mut self) {
+        self.tick_b();
+        self.record_mutex_statistics();
+        self.do_per_connection(|c, a, m| {
+            c.tick(a, m);
+        });
+        self.timeline.record(self.simulation_snapshot());
+    }
+
+    fn simulation_snapshot(&self) -> timeline::SimulationSnapshot {
+        timeline::SimulationSnapshot {
+            tick: self.current_tick,
+            activities: self
+                .activity_nodes
+                .iter()
+                .map(|(id, node)| (*id, node.simulation_state()))
+                .collect(),
+            mutexes: self
+                .mutex_nodes
+                .iter()
+                .map(|(id, node)| (*id, node.simulation_state()))
+                .collect(),
+            barriers: self
+                .barrier_nodes
+                .iter()
+                .map(|(id, node)| (*id, node.simulation_state()))
+                .collect(),
+            queues: self
+                .queue_nodes
+                .iter()
+                .map(|(id, node)| (*id, node.simulation_state()))
+                .collect(),
+            connections: self
+                .connections
+                .iter()
+                .flat_map(|(activity_id, activity_connections)| {
+                    activity_connections
+                        .iter()
+                        .map(|(mutex_id, connection)| (*activity_id, *mutex_id, connection.clone()))
+                })
+                .collect(),
+            statistics: self.statistics.clone(),
+        }
+    }
+
+    pub fn get_trace(&self) -> &trace::Trace {
+        &self.trace
+    }
+
+    pub fn get_timeline_range(&self) -> Option<std::ops::RangeInclusive<u64>> {
+        self.timeline.range()
+    }
+
+    fn apply_simulation_state(
+        &mut self,
+        activities: &[(ActivityNodeId, timeline::ActivityState)],
+        mutexes: &[(MutexNodeId, timeline::MutexState)],
+        barriers: &[(BarrierNodeId, timeline::BarrierState)],
+        queues: &[(QueueNodeId, timeline::QueueState)],
+    ) {
+        for (id, state) in activities {
+            if let Some(activity_node) = self.activity_nodes.get_mut(id) {
+                activity_node.restore_simulation_state(state);
+            }
+        }
+        for (id, state) in mutexes {
+            if let Some(mutex_node) = self.mutex_nodes.get_mut(id) {
+                mutex_node.restore_simulation_state(state);
+            }
+        }
+        for (id, state) in barriers {
+            if let Some(barrier_node) = self.barrier_nodes.get_mut(id) {
+                barrier_node.restore_simulation_state(state);
+            }
+        }
+        for (id, state) in queues {
+            if let Some(queue_node) = self.queue_nodes.get_mut(id) {
+                queue_node.restore_simulation_state(state);
             }
         }
     }
 
+    fn apply_connection_states(
+        &mut self,
+        connections: &[(ActivityNodeId, MutexNodeId, connection::Connection)],
+    ) {
+        for (activity_id, mutex_id, recorded_connection) in connections {
+            if let Some(connection) = self
+                .connections
+                .get_mut(activity_id)
+                .and_then(|activity_connections| activity_connections.get_mut(mutex_id))
+            {
+                connection.restore_states(recorded_connection);
+            }
+        }
+    }
+
+    // restores the recorded state at the start of the given tick and pauses the simulation,
+    // everything recorded about the later ticks is discarded as they did not happen anymore
+    pub fn rewind_to(&mut self, tick: u64) {
+        let Some(snapshot) = self.timeline.get(tick).cloned() else {
+            return;
+        };
+        self.timeline.discard_after(tick);
+        self.trace.discard_from(tick);
+        self.schedule.discard_from(tick);
+        self.apply_simulation_state(
+            &snapshot.activities,
+            &snapshot.mutexes,
+            &snapshot.barriers,
+            &snapshot.queues,
+        );
+        self.apply_connection_states(&snapshot.connections);
+        self.statistics = snapshot.statistics;
+        self.current_tick = snapshot.tick;
+        self.tick_progress = 0.;
+        self.remaining_ticks_to_run = 0;
+        self.deadlock = None;
+        self.deadline_miss = None;
+    }
+
     fn tick_a(&mut self) {
-        let base_seed = rand::random::<u64>();
-        self.activity_nodes
-            .sort_by(|&id_1, activity_node_1, &id_2, activity_node_2| {
-                match activity_node_1.priority.cmp(&activity_node_2.priority) {
-                    // randomize if priority is the same
-                    std::cmp::Ordering::Equal => {
-                        let random_1 =
-                            rand::rngs::StdRng::seed_from_u64(base_seed.wrapping_add(*id_1 as u64))
-                                .gen::<u64>();
-                        let random_2 =
-                            rand::rngs::StdRng::seed_from_u64(base_seed.wrapping_add(*id_2 as u64))
-                                .gen::<u64>();
-                        random_1.cmp(&random_2)
-                    }
-                    ordering => ordering,
+        self.refill_mutexes();
+        self.release_activities();
+        self.detect_deadline_misses();
+        let was_preempted = self
+            .activity_nodes
+            .iter()
+            .map(|(id, activity_node)| (*id, activity_node.preempted))
+            .collect::<Vec<_>>();
+        self.suspend_for_preemption();
+        for activity_id in self.scheduling_order() {
+            self.schedule_activity(activity_id);
+        }
+
+        for (activity_id, was_preempted) in was_preempted {
+            let activity_node = &self.activity_nodes[&activity_id];
+            let event = match (was_preempted, activity_node.preempted) {
+                (false, true) => trace::EventKind::ActivityPreempted,
+                (true, false) if activity_node.remaining_duration > 0 => {
+                    trace::EventKind::ActivityResumed
                 }
-            });
-        self.activity_nodes
-            .iter_mut()
-            .rev()
-            .for_each(|(activity_id, activity_node)| {
-                if activity_node.remaining_duration > 0 {
-                    return;
+                _ => continue,
+            };
+            self.trace.push(
+                self.current_tick,
+                event,
+                *activity_id,
+                activity_node.remaining_duration,
+            );
+        }
+    }
+
+    // periodic mutexes gain a unit, unless they are full
+    fn refill_mutexes(&mut self) {
+        let mut mutex_ids = self
+            .mutex_nodes
+            .iter()
+            .filter(|(_, mutex_node)| {
+                mutex_node.is_refilled_in(self.current_tick) && !mutex_node.is_full()
+            })
+            .map(|(mutex_id, _)| *mutex_id)
+            .collect::<Vec<_>>();
+        mutex_ids.sort();
+        for mutex_id in mutex_ids {
+            if let Some(mutex_node) = self.mutex_nodes.get_mut(&mutex_id) {
+                mutex_node.value = mutex_node.value.saturating_add(1);
+                self.trace.push(
+                    self.current_tick,
+                    trace::EventKind::MutexIncremented,
+                    *mutex_id,
+                    mutex_node.value,
+                );
+            }
+        }
+    }
+
+    fn release_activities(&mut self) {
+        for activity_id in self.activity_nodes.keys().copied().collect::<Vec<_>>() {
+            let activity_node = &self.activity_nodes[&activity_id];
+            let released = match activity_node.is_periodic() {
+                true => activity_node.is_released_in(self.current_tick),
+                // released as soon as it is ready to start
+                false => !activity_node.has_unfinished_run() && !self.is_blocked(activity_id),
+            };
+            if !released {
+                continue;
+            }
+            let activity_node = &mut self.activity_nodes[&activity_id];
+            if !activity_node.has_unfinished_run() {
+                activity_node.release_tick = self.current_tick;
+            }
+            activity_node.pending_releases += 1;
+            self.trace.push(
+                self.current_tick,
+                trace::EventKind::ActivityReleased,
+                *activity_id,
+                activity_node.pending_releases,
+            );
+        }
+    }
+
+    fn detect_deadline_misses(&mut self) {
+        for (activity_id, activity_node) in &mut self.activity_nodes {
+            let missed = !activity_node.deadline_missed
+                && activity_node.has_unfinished_run()
+                && activity_node
+                    .absolute_deadline()
+                    .map_or(false, |deadline| self.current_tick >= deadline);
+            if !missed {
+                continue;
+            }
+            activity_node.deadline_missed = true;
+            self.statistics.record_deadline_miss(*activity_id);
+            self.trace.push(
+                self.current_tick,
+                trace::EventKind::DeadlineMissed,
+                **activity_id,
+                activity_node.remaining_duration,
+            );
+            if self.deadline_miss.is_none() {
+                self.deadline_miss = Some(DeadlineMiss {
+                    tick: self.current_tick,
+                    activity: *activity_id,
+                });
+            }
+        }
+    }
+
+    pub fn get_deadline_miss(&self) -> Option<&DeadlineMiss> {
+        self.deadline_miss.as_ref()
+    }
+
+    pub fn dismiss_deadline_miss(&mut self) {
+        self.deadline_miss = None;
+    }
+
+    // started activities that have to get a core again just like new ones
+    fn suspend_for_preemption(&mut self) {
+        for activity_id in self.activity_nodes.keys().copied().collect::<Vec<_>>() {
+            let suspend = match self.scheduling_policy {
+                SchedulingPolicy::RoundRobin => self.time_slice_expired(activity_id),
+                _ => self.preemptive,
+            };
+            let activity_node = &mut self.activity_nodes[&activity_id];
+            if suspend && activity_node.remaining_duration > 0 {
+                activity_node.preempted = true;
+            }
+        }
+    }
+
+    fn time_slice_expired(&self, activity_id: ActivityNodeId) -> bool {
+        self.scheduling_policy == SchedulingPolicy::RoundRobin
+            && self.activity_nodes[&activity_id].ticks_in_slice >= self.quantum
+    }
+
+    // resumes the activity if it is preempted, or starts it if it is idle and not blocked,
+    // as long as a core is free
+    fn schedule_activity(&mut self, activity_id: ActivityNodeId) {
+        let activity_node = &self.activity_nodes[&activity_id];
+        if activity_node.preempted {
+            if self.has_free_core() {
+                let new_time_slice = self.time_slice_expired(activity_id);
+                let current_tick = self.current_tick;
+                let activity_node = &mut self.activity_nodes[&activity_id];
+                activity_node.preempted = false;
+                if new_time_slice {
+                    activity_node.ticks_in_slice = 0;
+                    activity_node.last_start_tick = Some(current_tick);
                 }
+            }
+        } else if activity_node.remaining_duration == 0
+            && (!activity_node.is_periodic() || activity_node.pending_releases > 0)
+            && self.has_free_core()
+            && !self.is_blocked(activity_id)
+        {
+            self.start_activity(activity_id);
+        }
+    }
 
-                let activity_connections = self.connections.get(activity_id);
+    fn has_free_core(&self) -> bool {
+        self.core_count.map_or(true, |core_count| {
+            self.activity_nodes
+                .values()
+                .filter(|activity_node| activity_node.is_running())
+                .count()
+                < core_count as usize
+        })
+    }
 
-                // check if prerequisites are met
-                let prerequisites_missing =
-                    activity_connections.map_or(false, |activity_connections| {
-                        activity_connections
-                            .iter()
-                            .filter(|(_, connection)| {
-                                connection.get_direction() != Direction::ActivityToMutex
-                            })
-                            .filter_map(|(mutex_id, _)| self.mutex_nodes.get(mutex_id))
-                            .any(|mutex_node| mutex_node.value == 0)
-                    });
-                if prerequisites_missing {
-                    return;
+    // all activity ids, the one to be considered first comes first
+    fn scheduling_order(&self) -> Vec<ActivityNodeId> {
+        let base_seed = rand::rngs::StdRng::seed_from_u64(
+            (self.seed as u64) ^ self.current_tick.wrapping_mul(0x9E37_79B9_7F4A_7C15),
+        )
+        .gen::<u64>();
+        self.precedence_groups()
+            .into_iter()
+            .flat_map(|mut group| {
+                if self.scheduling_policy == SchedulingPolicy::RoundRobin {
+                    // the activity that waited the longest for a time slice goes first
+                    group.sort_by_key(|id| (self.activity_nodes[id].last_start_tick, *id));
+                    return group;
                 }
+                match self.tie_breaking {
+                    TieBreaking::Random => group.sort_by_cached_key(|id| {
+                        rand::rngs::StdRng::seed_from_u64(base_seed.wrapping_add(**id as u64))
+                            .gen::<u64>()
+                    }),
+                    TieBreaking::LowestId => group.sort(),
+                    TieBreaking::RoundRobin => {
+                        // the least recently started activity goes first
+                        group.sort_by_key(|id| (self.activity_nodes[id].last_start_tick, *id))
+                    }
+                }
+                // activities only get preempted by a higher priority
+                group.sort_by_key(|id| self.activity_nodes[id].remaining_duration == 0);
+                group
+            })
+            .collect()
+    }
 
-                // start the node
-                activity_node.remaining_duration = activity_node.duration;
+    // activity ids grouped by equal precedence under the scheduling policy, highest first
+    fn precedence_groups(&self) -> Vec<Vec<ActivityNodeId>> {
+        let effective_priorities = self.effective_priorities();
+        let precedence_key = |id| self.precedence_key(id, &effective_priorities);
+        let mut ids = self.activity_nodes.keys().copied().collect::<Vec<_>>();
+        ids.sort_by_key(|id| (precedence_key(*id), *id));
+
+        let mut groups: Vec<Vec<ActivityNodeId>> = Vec::new();
+        for id in ids {
+            match groups.last_mut() {
+                Some(group) if precedence_key(group[0]) == precedence_key(id) => group.push(id),
+                _ => groups.push(vec![id]),
+            }
+        }
+        groups
+    }
 
-                // decrement prerequisites
-                if let Some(activity_connections) = activity_connections {
-                    activity_connections
-                        .iter()
-                        .for_each(|(mutex_id, connection)| {
-                            if connection.get_direction() != Direction::ActivityToMutex {
-                                if let Some(mutex_node) = self.mutex_nodes.get_mut(mutex_id) {
-                                    mutex_node.value -= 1;
+    // lower keys go first
+    fn precedence_key(
+        &self,
+        activity_id: ActivityNodeId,
+        effective_priorities: &std::collections::HashMap<ActivityNodeId, u32>,
+    ) -> u64 {
+        let activity_node = &self.activity_nodes[&activity_id];
+        match self.scheduling_policy {
+            SchedulingPolicy::FixedPriority => {
+                u64::MAX
+                    - effective_priorities
+                        .get(&activity_id)
+                        .copied()
+                        .unwrap_or(activity_node.priority) as u64
+            }
+            SchedulingPolicy::EarliestDeadlineFirst => {
+                activity_node.absolute_deadline().unwrap_or(u64::MAX)
+            }
+            SchedulingPolicy::RateMonotonic => match activity_node.period {
+                0 => u64::MAX,
+                period => period as u64,
+            },
+            SchedulingPolicy::RoundRobin => 0,
+        }
+    }
+
+    // the priorities after applying the priority protocol
+    pub fn effective_priorities(&self) -> std::collections::HashMap<ActivityNodeId, u32> {
+        let mut priorities = self
+            .activity_nodes
+            .iter()
+            .map(|(id, activity_node)| (*id, activity_node.priority))
+            .collect::<std::collections::HashMap<_, _>>();
+        match self.priority_protocol {
+            PriorityProtocol::None => {}
+            PriorityProtocol::Ceiling => {
+                for mutex_id in self.mutex_nodes.keys() {
+                    let ceiling = self.priority_ceiling(*mutex_id);
+                    for holder_id in self.mutex_holders(*mutex_id) {
+                        if let Some(priority) = priorities.get_mut(&holder_id) {
+                            *priority = (*priority).max(ceiling);
+                        }
+                    }
+                }
+            }
+            PriorityProtocol::Inheritance => {
+                // repeated until nothing changes, so priorities are passed along chains of holders
+                let waiting_activities = self
+                    .activity_nodes
+                    .iter()
+                    .filter(|(_, activity_node)| {
+                        activity_node.remaining_duration == 0
+                            && (!activity_node.is_periodic() || activity_node.pending_releases > 0)
+                    })
+                    .map(|(id, _)| (*id, self.blocking_mutexes(*id)))
+                    .collect::<Vec<_>>();
+                let holders = self
+                    .mutex_nodes
+                    .keys()
+                    .map(|mutex_id| (*mutex_id, self.mutex_holders(*mutex_id)))
+                    .collect::<std::collections::HashMap<_, _>>();
+                let mut changed = true;
+                while changed {
+                    changed = false;
+                    for (waiting_id, blocking_mutexes) in &waiting_activities {
+                        let waiting_priority = priorities[waiting_id];
+                        for holder_id in blocking_mutexes
+                            .iter()
+                            .filter_map(|mutex_id| holders.get(mutex_id))
+                            .flatten()
+                        {
+                            if let Some(priority) = priorities.get_mut(holder_id) {
+                                if *priority < waiting_priority {
+                                    *priority = waiting_priority;
+                                    changed = true;
                                 }
                             }
-                        });
+                        }
+                    }
                 }
-            });
+            }
+        }
+        priorities
+    }
+
+    // started activities that took units from the mutex and give them back when they finish,
+    // and all activities of tasks that give them back in a later activity
+    pub fn mutex_holders(&self, mutex_id: MutexNodeId) -> Vec<ActivityNodeId> {
+        let held_by = self
+            .mutex_nodes
+            .get(&mutex_id)
+            .map(|mutex_node| mutex_node.held_by.clone())
+            .unwrap_or_default();
+        let mut holders = self
+            .connections
+            .iter()
+            .filter(|(activity_id, activity_connections)| {
+                self.activity_nodes
+                    .get(*activity_id)
+                    .map_or(false, |activity_node| activity_node.remaining_duration > 0)
+                    && activity_connections
+                        .get(&mutex_id)
+                        .map_or(false, |connection| {
+                            connection.get_direction() == Direction::TwoWay
+                                && connection.get_input_kind() == connection::InputKind::Consume
+                        })
+            })
+            .map(|(activity_id, _)| *activity_id)
+            .chain(
+                self.activity_nodes
+                    .iter()
+                    .filter(|(_, activity_node)| held_by.contains(&activity_node.task_name))
+                    .map(|(activity_id, _)| *activity_id),
+            )
+            .collect::<Vec<_>>();
+        holders.sort();
+        holders.dedup();
+        holders
+    }
+
+    // mutexes the activity takes units from, which a later activity of its task gives back
+    fn critical_section_mutexes(&self, activity_id: ActivityNodeId) -> Vec<MutexNodeId> {
+        let Some(activity_node) = self.activity_nodes.get(&activity_id) else {
+            return Vec::new();
+        };
+        let Some(activity_connections) = self.connections.get(&activity_id) else {
+            return Vec::new();
+        };
+        let mut mutex_ids = activity_connections
+            .iter()
+            .filter(|(_, connection)| {
+                connection.get_direction() == Direction::MutexToActivity
+                    && connection.get_input_kind() == connection::InputKind::Consume
+            })
+            .map(|(mutex_id, _)| *mutex_id)
+            .filter(|mutex_id| {
+                self.connections
+                    .iter()
+                    .filter(|(other_id, _)| {
+                        **other_id != activity_id
+                            && self
+                                .activity_nodes
+                                .get(*other_id)
+                                .map_or(false, |other| other.task_name == activity_node.task_name)
+                    })
+                    .filter_map(|(_, other_connections)| other_connections.get(mutex_id))
+                    .any(|connection| {
+                        connection.get_direction() == Direction::ActivityToMutex
+                            && connection.get_output_kind() == connection::OutputKind::Produce
+                    })
+            })
+            .collect::<Vec<_>>();
+        mutex_ids.sort();
+        mutex_ids
+    }
+
+    // the highest priority of all activities that take units from the mutex
+    fn priority_ceiling(&self, mutex_id: MutexNodeId) -> u32 {
+        self.connections
+            .iter()
+            .filter(|(_, activity_connections)| {
+                activity_connections
+                    .get(&mutex_id)
+                    .map_or(false, |connection| {
+                        connection.get_direction() != Direction::ActivityToMutex
+                            && connection.get_input_kind() == connection::InputKind::Consume
+                    })
+            })
+            .filter_map(|(activity_id, _)| self.activity_nodes.get(activity_id))
+            .map(|activity_node| activity_node.priority)
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn is_blocked(&self, activity_id: ActivityNodeId) -> bool {
+        !self.blocking_mutexes(activity_id).is_empty()
+            || !self.blocking_barriers(activity_id).is_empty()
+            || !self.blocking_locks(activity_id).is_empty()
+            || !self.blocking_queues(activity_id).is_empty()
+    }
 
-        // return to predictable order for drawing the ui
-        self.activity_nodes.sort_unstable_keys();
+    // empty queues the activity consumes from
+    fn blocking_queues(&self, activity_id: ActivityNodeId) -> Vec<QueueNodeId> {
+        let mut blocking_queues = self
+            .queue_nodes
+            .iter()
+            .filter(|(_, queue_node)| {
+                queue_node.consumers.contains(&activity_id) && queue_node.tokens.is_empty()
+            })
+            .map(|(queue_id, _)| *queue_id)
+            .collect::<Vec<_>>();
+        blocking_queues.sort();
+        blocking_queues
+    }
+
+    // started activities hold the locks they are connected to
+    pub fn lock_holders(&self, lock_id: LockNodeId) -> Vec<(ActivityNodeId, LockAccess)> {
+        self.lock_nodes
+            .get(&lock_id)
+            .map(|lock_node| {
+                lock_node
+                    .access
+                    .iter()
+                    .filter(|(activity_id, _)| {
+                        self.activity_nodes
+                            .get(*activity_id)
+                            .map_or(false, |activity_node| activity_node.remaining_duration > 0)
+                    })
+                    .map(|(activity_id, access)| (*activity_id, *access))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    // locks the activity can not acquire next to their current holders
+    fn blocking_locks(&self, activity_id: ActivityNodeId) -> Vec<LockNodeId> {
+        let mut blocking_locks = self
+            .lock_nodes
+            .iter()
+            .filter(|(lock_id, lock_node)| {
+                lock_node.access.get(&activity_id).map_or(false, |access| {
+                    !access.is_compatible_with(
+                        self.lock_holders(**lock_id)
+                            .into_iter()
+                            .filter(|(holder_id, _)| *holder_id != activity_id)
+                            .map(|(_, access)| access),
+                    )
+                })
+            })
+            .map(|(lock_id, _)| *lock_id)
+            .collect::<Vec<_>>();
+        blocking_locks.sort();
+        blocking_locks
+    }
+
+    // barriers the activity waits for, that did not release it yet
+    fn blocking_barriers(&self, activity_id: ActivityNodeId) -> Vec<BarrierNodeId> {
+        let mut blocking_barriers = self
+            .barrier_nodes
+            .iter()
+            .filter(|(_, barrier_node)| {
+                barrier_node.waiting.contains(&activity_id) && !barrier_node.has_pass(activity_id)
+            })
+            .map(|(barrier_id, _)| *barrier_id)
+            .collect::<Vec<_>>();
+        blocking_barriers.sort();
+        blocking_barriers
+    }
+
+    // mutexes that currently prevent the activity from starting,
+    // inputs without enough units and outputs that would overflow their capacity
+    fn blocking_mutexes(&self, activity_id: ActivityNodeId) -> Vec<MutexNodeId> {
+        self.connections
+            .get(&activity_id)
+            .map(|activity_connections| {
+                activity_connections
+                    .iter()
+                    .filter(|(mutex_id, connection)| {
+                        let Some(mutex_node) = self.mutex_nodes.get(mutex_id) else {
+                            return false;
+                        };
+                        let (allowed, consumed) = match connection.get_direction() {
+                            Direction::ActivityToMutex => (true, 0),
+                            _ => (
+                                connection
+                                    .get_input_kind()
+                                    .allows_start(mutex_node.value, connection.get_input_weight()),
+                                connection
+                                    .get_input_kind()
+                                    .consumed_units(connection.get_input_weight()),
+                            ),
+                        };
+                        let produced = match connection.get_direction() {
+                            Direction::MutexToActivity => 0,
+                            _ => connection
+                                .get_output_kind()
+                                .produced_units(connection.get_output_weight()),
+                        };
+                        !allowed
+                            || mutex_node.capacity.map_or(false, |capacity| {
+                                produced > 0
+                                    && (mutex_node.value - consumed)
+                                        .saturating_add(self.reserved_units(**mutex_id))
+                                        .saturating_add(produced)
+                                        > capacity
+                            })
+                    })
+                    .map(|(mutex_id, _)| *mutex_id)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    // units that started activities will produce into the mutex once they finish
+    fn reserved_units(&self, mutex_id: MutexNodeId) -> u32 {
+        self.connections
+            .iter()
+            .filter(|(activity_id, _)| {
+                self.activity_nodes
+                    .get(*activity_id)
+                    .map_or(false, |activity_node| activity_node.remaining_duration > 0)
+            })
+            .filter_map(|(_, activity_connections)| activity_connections.get(&mutex_id))
+            .filter(|connection| connection.get_direction() != Direction::MutexToActivity)
+            .map(|connection| {
+                connection
+                    .get_output_kind()
+                    .produced_units(connection.get_output_weight())
+            })
+            .sum()
+    }
+
+    fn start_activity(&mut self, activity_id: ActivityNodeId) {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(
+            ((self.seed as u64) << 32)
+                ^ self.current_tick.wrapping_mul(0x9E37_79B9_7F4A_7C15)
+                ^ (*activity_id as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F),
+        );
+        let Some(activity_node) = self.activity_nodes.get_mut(&activity_id) else {
+            return;
+        };
+        activity_node.current_duration = activity_node
+            .duration_distribution
+            .sample(activity_node.duration, &mut rng);
+        activity_node.remaining_duration = activity_node.current_duration;
+        activity_node.last_start_tick = Some(self.current_tick);
+        activity_node.ticks_in_slice = 0;
+        activity_node.pending_releases = activity_node.pending_releases.saturating_sub(1);
+        self.trace.push(
+            self.current_tick,
+            trace::EventKind::ActivityStarted,
+            *activity_id,
+            activity_node.remaining_duration,
+        );
+
+        // the task holds these mutexes until a later activity gives the units back
+        let task_name = activity_node.task_name.clone();
+        for mutex_id in self.critical_section_mutexes(activity_id) {
+            if let Some(mutex_node) = self.mutex_nodes.get_mut(&mutex_id) {
+                mutex_node.held_by.insert(task_name.clone());
+            }
+        }
+
+        // decrement prerequisites
+        if let Some(activity_connections) = self.connections.get(&activity_id) {
+            sorted_by_mutex_id(activity_connections)
+                .into_iter()
+                .for_each(|(mutex_id, connection)| {
+                    let consumed_units = connection
+                        .get_input_kind()
+                        .consumed_units(connection.get_input_weight());
+                    if connection.get_direction() != Direction::ActivityToMutex
+                        && consumed_units > 0
+                    {
+                        if let Some(mutex_node) = self.mutex_nodes.get_mut(mutex_id) {
+                            mutex_node.value = mutex_node.value.saturating_sub(consumed_units);
+                            self.trace.push(
+                                self.current_tick,
+                                trace::EventKind::MutexDecremented,
+                                **mutex_id,
+                                mutex_node.value,
+                            );
+                        }
+                    }
+                });
+        }
+
+        let mut lock_ids = self
+            .lock_nodes
+            .iter()
+            .filter(|(_, lock_node)| lock_node.access.contains_key(&activity_id))
+            .map(|(lock_id, _)| *lock_id)
+            .collect::<Vec<_>>();
+        lock_ids.sort();
+        for lock_id in lock_ids {
+            let holder_count = self.lock_holders(lock_id).len() as u32;
+            self.trace.push(
+                self.current_tick,
+                trace::EventKind::LockAcquired,
+                *lock_id,
+                holder_count,
+            );
+        }
+
+        // pop a token from every queue it consumes from
+        let mut queue_ids = self
+            .queue_nodes
+            .iter()
+            .filter(|(_, queue_node)| queue_node.consumers.contains(&activity_id))
+            .map(|(queue_id, _)| *queue_id)
+            .collect::<Vec<_>>();
+        queue_ids.sort();
+        for queue_id in queue_ids {
+            if let Some(queue_node) = self.queue_nodes.get_mut(&queue_id) {
+                queue_node.pop(activity_id);
+                self.trace.push(
+                    self.current_tick,
+                    trace::EventKind::QueuePopped,
+                    *queue_id,
+                    queue_node.tokens.len() as u32,
+                );
+            }
+        }
+
+        // use up the releases of the barriers it waited for
+        for barrier_node in self.barrier_nodes.values_mut() {
+            if barrier_node.waiting.contains(&activity_id) {
+                barrier_node.take_pass(activity_id);
+            }
+        }
+    }
+
+    // nothing is running and nothing can start anymore
+    fn detect_deadlock(&self) -> Option<Deadlock> {
+        if self.activity_nodes.is_empty()
+            || self
+                .activity_nodes
+                .values()
+                .any(|activity_node| activity_node.remaining_duration > 0)
+            // a periodic mutex will still gain units
+            || self
+                .mutex_nodes
+                .values()
+                .any(|mutex_node| mutex_node.is_periodic() && !mutex_node.is_full())
+        {
+            return None;
+        }
+
+        let mut blocking_mutexes = std::collections::BTreeSet::new();
+        let mut blocking_barriers = std::collections::BTreeSet::new();
+        let mut blocking_queues = std::collections::BTreeSet::new();
+        for activity_id in self.activity_nodes.keys() {
+            let activity_blocking_mutexes = self.blocking_mutexes(*activity_id);
+            let activity_blocking_barriers = self.blocking_barriers(*activity_id);
+            let activity_blocking_queues = self.blocking_queues(*activity_id);
+            if activity_blocking_mutexes.is_empty()
+                && activity_blocking_barriers.is_empty()
+                && activity_blocking_queues.is_empty()
+            {
+                return None;
+            }
+            blocking_mutexes.extend(activity_blocking_mutexes);
+            blocking_barriers.extend(activity_blocking_barriers);
+            blocking_queues.extend(activity_blocking_queues);
+        }
+
+        let mut blocked_activities = self.activity_nodes.keys().copied().collect::<Vec<_>>();
+        blocked_activities.sort();
+        Some(Deadlock {
+            tick: self.current_tick,
+            blocked_activities,
+            blocking_mutexes: blocking_mutexes.into_iter().collect(),
+            blocking_barriers: blocking_barriers.into_iter().collect(),
+            blocking_queues: blocking_queues.into_iter().collect(),
+        })
+    }
+
+    pub fn get_deadlock(&self) -> Option<&Deadlock> {
+        self.deadlock.as_ref()
+    }
+
+    pub fn dismiss_deadlock(&mut self) {
+        self.deadlock = None;
     }
 
     fn tick_b(&mut self) {
+        let mut finished_activities = Vec::new();
         for (activity_id, activity_node) in &mut self.activity_nodes {
-            if activity_node.remaining_duration == 0 {
+            if !activity_node.is_running() {
                 continue;
             }
             activity_node.remaining_duration -= 1;
+            activity_node.ticks_in_slice += 1;
 
             if activity_node.remaining_duration == 0 {
+                self.trace.push(
+                    self.current_tick,
+                    trace::EventKind::ActivityFinished,
+                    **activity_id,
+                    0,
+                );
+                self.statistics.record_completed_run(*activity_id);
+                finished_activities.push(*activity_id);
+                activity_node.deadline_missed = false;
+                // runs of periodic activities are released exactly one period apart
+                if activity_node.is_periodic() && activity_node.pending_releases > 0 {
+                    activity_node.release_tick += activity_node.period as u64;
+                }
                 if let Some(activity_connections) = self.connections.get(activity_id) {
-                    // increment all outputs
-                    activity_connections
-                        .iter()
+                    // increment or reset all outputs
+                    sorted_by_mutex_id(activity_connections)
+                        .into_iter()
                         .for_each(|(mutex_id, connection)| {
                             if connection.get_direction() != Direction::MutexToActivity {
                                 if let Some(mutex_node) = self.mutex_nodes.get_mut(mutex_id) {
-                                    mutex_node.value += 1;
+                                    if connection.get_output_kind()
+                                        == connection::OutputKind::Produce
+                                    {
+                                        mutex_node.held_by.remove(&activity_node.task_name);
+                                    }
+                                    mutex_node.value = connection
+                                        .get_output_kind()
+                                        .apply(mutex_node.value, connection.get_output_weight());
+                                    self.trace.push(
+                                        self.current_tick,
+                                        match connection.get_output_kind() {
+                                            connection::OutputKind::Produce => {
+                                                trace::EventKind::MutexIncremented
+                                            }
+                                            connection::OutputKind::Reset => {
+                                                trace::EventKind::MutexReset
+                                            }
+                                        },
+                                        **mutex_id,
+                                        mutex_node.value,
+                                    );
                                 }
                             }
                         })
                 }
+
+                // push the label into every queue it produces for
+                let mut queue_ids = self
+                    .queue_nodes
+                    .iter()
+                    .filter(|(_, queue_node)| queue_node.producers.contains_key(activity_id))
+                    .map(|(queue_id, _)| *queue_id)
+                    .collect::<Vec<_>>();
+                queue_ids.sort();
+                for queue_id in queue_ids {
+                    if let Some(queue_node) = self.queue_nodes.get_mut(&queue_id) {
+                        queue_node.push(*activity_id);
+                        self.trace.push(
+                            self.current_tick,
+                            trace::EventKind::QueuePushed,
+                            *queue_id,
+                            queue_node.tokens.len() as u32,
+                        );
+                    }
+                }
+
+                // arrive at all barriers, in a deterministic order
+                let mut barrier_ids = self
+                    .barrier_nodes
+                    .iter()
+                    .filter(|(_, barrier_node)| barrier_node.arriving.contains(activity_id))
+                    .map(|(barrier_id, _)| *barrier_id)
+                    .collect::<Vec<_>>();
+                barrier_ids.sort();
+                for barrier_id in barrier_ids {
+                    if let Some(barrier_node) = self.barrier_nodes.get_mut(&barrier_id) {
+                        let released = barrier_node.arrive(*activity_id);
+                        self.trace.push(
+                            self.current_tick,
+                            trace::EventKind::BarrierArrived,
+                            *barrier_id,
+                            barrier_node.arrived.len() as u32,
+                        );
+                        if released {
+                            self.trace.push(
+                                self.current_tick,
+                                trace::EventKind::BarrierReleased,
+                                *barrier_id,
+                                barrier_node.waiting.len() as u32,
+                            );
+                        }
+                    }
+                }
             }
         }
+
+        // release the locks of the finished activities
+        let mut lock_ids = self
+            .lock_nodes
+            .iter()
+            .filter(|(_, lock_node)| {
+                finished_activities
+                    .iter()
+                    .any(|activity_id| lock_node.access.contains_key(activity_id))
+            })
+            .map(|(lock_id, _)| *lock_id)
+            .collect::<Vec<_>>();
+        lock_ids.sort();
+        for lock_id in lock_ids {
+            let holder_count = self.lock_holders(lock_id).len() as u32;
+            self.trace.push(
+                self.current_tick,
+                trace::EventKind::LockReleased,
+                *lock_id,
+                holder_count,
+            );
+        }
+
+        self.current_tick += 1;
+    }
+
+    pub fn get_current_tick(&self) -> u64 {
+        self.current_tick
     }
 
     pub fn is_running(&self) -> bool {
@@ -650,6 +2545,122 @@ impl Graph {
     }
 }
 
+// history
+impl Graph {
+    // the current structure, with the current values of the given value edits
+    fn snapshot(&self, value_edits: &[history::ValueEdit]) -> history::Snapshot {
+        history::Snapshot {
+            activity_nodes: self.activity_nodes.clone(),
+            mutex_nodes: self.mutex_nodes.clone(),
+            barrier_nodes: self.barrier_nodes.clone(),
+            lock_nodes: self.lock_nodes.clone(),
+            queue_nodes: self.queue_nodes.clone(),
+            connections: self.connections.clone(),
+            next_activity_id: self.next_activity_id,
+            next_mutex_id: self.next_mutex_id,
+            next_barrier_id: self.next_barrier_id,
+            next_lock_id: self.next_lock_id,
+            next_queue_id: self.next_queue_id,
+            value_edits: value_edits
+                .iter()
+                .filter_map(|value_edit| match *value_edit {
+                    history::ValueEdit::MutexValue(id, _) => self
+                        .mutex_nodes
+                        .get(&id)
+                        .map(|mutex_node| history::ValueEdit::MutexValue(id, mutex_node.value)),
+                    history::ValueEdit::RemainingDuration(id, _) => {
+                        self.activity_nodes.get(&id).map(|activity_node| {
+                            history::ValueEdit::RemainingDuration(
+                                id,
+                                activity_node.remaining_duration,
+                            )
+                        })
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    // keeps the simulation state of the nodes that still exist, so it stays consistent
+    // with the current tick, the timeline, the trace and the statistics
+    fn restore(&mut self, snapshot: history::Snapshot) {
+        let simulation_state = self.simulation_snapshot();
+        self.activity_nodes = snapshot.activity_nodes;
+        self.mutex_nodes = snapshot.mutex_nodes;
+        self.barrier_nodes = snapshot.barrier_nodes;
+        self.lock_nodes = snapshot.lock_nodes;
+        self.queue_nodes = snapshot.queue_nodes;
+        self.connections = snapshot.connections;
+        self.next_activity_id = snapshot.next_activity_id;
+        self.next_mutex_id = snapshot.next_mutex_id;
+        self.next_barrier_id = snapshot.next_barrier_id;
+        self.next_lock_id = snapshot.next_lock_id;
+        self.next_queue_id = snapshot.next_queue_id;
+        self.apply_simulation_state(
+            &simulation_state.activities,
+            &simulation_state.mutexes,
+            &simulation_state.barriers,
+            &simulation_state.queues,
+        );
+        self.apply_connection_states(&simulation_state.connections);
+        for value_edit in snapshot.value_edits {
+            match value_edit {
+                history::ValueEdit::MutexValue(id, value) => {
+                    if let Some(mutex_node) = self.mutex_nodes.get_mut(&id) {
+                        mutex_node.value = value;
+                    }
+                }
+                history::ValueEdit::RemainingDuration(id, remaining_duration) => {
+                    if let Some(activity_node) = self.activity_nodes.get_mut(&id) {
+                        activity_node.remaining_duration = remaining_duration;
+                    }
+                }
+            }
+        }
+        self.currently_connecting_from = None;
+        self.history.set_edit_start(self.snapshot(&[]));
+    }
+
+    fn commit_edit(&mut self) {
+        if self.history.is_edited() {
+            self.history.commit_edit(self.snapshot(&[]));
+        }
+    }
+
+    // call once per frame, before any edits happen
+    pub fn track_history(&mut self, ctx: &egui::Context) {
+        if !self.history.has_edit_start() {
+            self.history.set_edit_start(self.snapshot(&[]));
+        } else if ctx.input(|i| i.pointer.any_pressed()) {
+            self.commit_edit();
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.history.can_undo()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.history.can_redo()
+    }
+
+    pub fn undo(&mut self) {
+        self.commit_edit();
+        if let Some(snapshot) = self.history.pop_undo() {
+            self.history.push_redo(self.snapshot(&snapshot.value_edits));
+            self.restore(snapshot);
+        }
+    }
+
+    pub fn redo(&mut self) {
+        self.commit_edit();
+        if let Some(snapshot) = self.history.pop_redo() {
+            self.history.push_undo(self.snapshot(&snapshot.value_edits));
+            self.restore(snapshot);
+        }
+    }
+}
+
 // ux
 impl Graph {
     pub fn queue_autofit(&mut self) {
@@ -685,6 +2696,18 @@ impl Graph {
                     let rect = egui::Rect::from_center_size(node.pos, egui::vec2(50., 50.));
                     bounding_rect = bounding_rect.union(rect);
                 });
+                self.barrier_nodes.iter().for_each(|(_, node)| {
+                    let rect = egui::Rect::from_center_size(node.pos, egui::vec2(60., 50.));
+                    bounding_rect = bounding_rect.union(rect);
+                });
+                self.lock_nodes.iter().for_each(|(_, node)| {
+                    let rect = egui::Rect::from_center_size(node.pos, egui::vec2(50., 60.));
+                    bounding_rect = bounding_rect.union(rect);
+                });
+                self.queue_nodes.iter().for_each(|(_, node)| {
+                    let rect = egui::Rect::from_center_size(node.pos, egui::vec2(80., 60.));
+                    bounding_rect = bounding_rect.union(rect);
+                });
 
                 if bounding_rect.is_positive() {
                     let scale_x = untransformed_viewport_rect.width() / bounding_rect.width();
@@ -701,28 +2724,42 @@ impl Graph {
         }
 
         // node interactions
-        let mut node_left_clicked = None;
-        let mut node_right_clicked = None;
-        self.activity_nodes.iter_mut().for_each(|(id, node)| {
-            if let Some(response) = node.interact(ui) {
-                if response.clicked() {
-                    node_left_clicked = Some(AnyNode::Activity(*id));
-                }
-                if response.secondary_clicked() {
-                    node_right_clicked = Some(AnyNode::Activity(*id));
-                }
-            }
-        });
-        self.mutex_nodes.iter_mut().for_each(|(id, node)| {
-            if let Some(response) = node.interact(ui) {
-                if response.clicked() {
-                    node_left_clicked = Some(AnyNode::Mutex(*id));
-                }
-                if response.secondary_clicked() {
-                    node_right_clicked = Some(AnyNode::Mutex(*id));
-                }
-            }
-        });
+        let mut interactions = NodeInteractions::default();
+        interactions.add(
+            self.activity_nodes.iter_mut(),
+            AnyNode::Activity,
+            |node| node.pos,
+            |node| node.interact(ui),
+        );
+        interactions.add(
+            self.mutex_nodes.iter_mut(),
+            AnyNode::Mutex,
+            |node| node.pos,
+            |node| node.interact(ui),
+        );
+        interactions.add(
+            self.barrier_nodes.iter_mut(),
+            AnyNode::Barrier,
+            |node| node.pos,
+            |node| node.interact(ui),
+        );
+        interactions.add(
+            self.lock_nodes.iter_mut(),
+            AnyNode::Lock,
+            |node| node.pos,
+            |node| node.interact(ui),
+        );
+        interactions.add(
+            self.queue_nodes.iter_mut(),
+            AnyNode::Queue,
+            |node| node.pos,
+            |node| node.interact(ui),
+        );
+        let node_left_clicked = interactions.left_clicked;
+        let mut node_right_clicked = interactions.right_clicked;
+        if interactions.moved {
+            self.history.mark_edited();
+        }
         if self.currently_connecting_from.is_none() {
             self.currently_connecting_from = node_right_clicked;
             node_right_clicked = None;
@@ -734,15 +2771,9 @@ impl Graph {
                     self.editing_mode = EditingMode::None;
                     return;
                 }
-                if let Some(AnyNode::Activity(id)) = node_left_clicked {
-                    self.activity_nodes.swap_remove(&id);
-                    self.connections.remove(&id);
-                }
-                if let Some(AnyNode::Mutex(id)) = node_left_clicked {
-                    self.mutex_nodes.remove(&id);
-                    self.connections.iter_mut().for_each(|(_, connections)| {
-                        connections.remove(&id);
-                    });
+                if let Some(node) = node_left_clicked {
+                    self.remove_node(node);
+                    self.history.mark_edited();
                 }
             }
             EditingMode::None => {
@@ -821,19 +2852,112 @@ impl Graph {
                         }
                         Some(AnyNode::Mutex(to_mutex_id))
                     }
+                    (
+                        Some(AnyNode::Activity(from_activity_id)),
+                        Some(AnyNode::Barrier(to_barrier_id)),
+                    ) => {
+                        self.toggle_barrier_connection(from_activity_id, to_barrier_id, true);
+                        Some(AnyNode::Barrier(to_barrier_id))
+                    }
+                    (
+                        Some(AnyNode::Barrier(from_barrier_id)),
+                        Some(AnyNode::Activity(to_activity_id)),
+                    ) => {
+                        self.toggle_barrier_connection(to_activity_id, from_barrier_id, false);
+                        Some(AnyNode::Activity(to_activity_id))
+                    }
+                    (
+                        Some(AnyNode::Activity(from_activity_id)),
+                        Some(AnyNode::Lock(to_lock_id)),
+                    ) => {
+                        self.toggle_lock_connection(from_activity_id, to_lock_id);
+                        Some(AnyNode::Lock(to_lock_id))
+                    }
+                    (
+                        Some(AnyNode::Lock(from_lock_id)),
+                        Some(AnyNode::Activity(to_activity_id)),
+                    ) => {
+                        self.toggle_lock_connection(to_activity_id, from_lock_id);
+                        Some(AnyNode::Activity(to_activity_id))
+                    }
+                    (
+                        Some(AnyNode::Activity(from_activity_id)),
+                        Some(AnyNode::Queue(to_queue_id)),
+                    ) => {
+                        self.toggle_queue_connection(from_activity_id, to_queue_id, true);
+                        Some(AnyNode::Queue(to_queue_id))
+                    }
+                    (
+                        Some(AnyNode::Queue(from_queue_id)),
+                        Some(AnyNode::Activity(to_activity_id)),
+                    ) => {
+                        self.toggle_queue_connection(to_activity_id, from_queue_id, false);
+                        Some(AnyNode::Activity(to_activity_id))
+                    }
                     _ => None,
                 } {
+                    self.history.mark_edited();
                     self.currently_connecting_from = match node_left_clicked.is_some() {
                         true => None,
                         false => Some(new_from_node),
                     };
                 }
 
-                // right click empty space (create nodes)
+                // right click empty space (create nodes, barriers while holding shift,
+                // locks while holding alt, queues while holding ctrl)
                 if container_response.secondary_clicked() {
                     if let Some(pos) = container_response.interact_pointer_pos() {
                         let pos = container_transform.inverse() * pos;
+                        let create_barrier = ui.input(|i| i.modifiers.shift);
+                        let create_lock = ui.input(|i| i.modifiers.alt);
+                        let create_queue = ui.input(|i| i.modifiers.command);
+                        self.history.mark_edited();
                         match self.currently_connecting_from {
+                            Some(AnyNode::Activity(activity_id)) if create_barrier => {
+                                let barrier_id = self.add_barrier_node(BarrierNode::new(pos));
+                                self.toggle_barrier_connection(activity_id, barrier_id, true);
+                                self.currently_connecting_from = Some(AnyNode::Barrier(barrier_id));
+                            }
+                            None if create_barrier => {
+                                self.add_barrier_node(BarrierNode::new(pos));
+                            }
+                            Some(AnyNode::Activity(activity_id)) if create_lock => {
+                                let lock_id = self.add_lock_node(LockNode::new(pos));
+                                self.toggle_lock_connection(activity_id, lock_id);
+                                self.currently_connecting_from = Some(AnyNode::Lock(lock_id));
+                            }
+                            None if create_lock => {
+                                self.add_lock_node(LockNode::new(pos));
+                            }
+                            Some(AnyNode::Activity(activity_id)) if create_queue => {
+                                let queue_id = self.add_queue_node(QueueNode::new(pos));
+                                self.toggle_queue_connection(activity_id, queue_id, true);
+                                self.currently_connecting_from = Some(AnyNode::Queue(queue_id));
+                            }
+                            None if create_queue => {
+                                self.add_queue_node(QueueNode::new(pos));
+                            }
+                            Some(AnyNode::Queue(queue_id)) => {
+                                let activity_id =
+                                    self.add_activity_node(Graph::new_random_activity(pos));
+                                self.toggle_queue_connection(activity_id, queue_id, false);
+                                self.currently_connecting_from =
+                                    Some(AnyNode::Activity(activity_id));
+                            }
+                            Some(AnyNode::Lock(lock_id)) => {
+                                let activity_id =
+                                    self.add_activity_node(Graph::new_random_activity(pos));
+                                self.toggle_lock_connection(activity_id, lock_id);
+                                self.currently_connecting_from =
+                                    Some(AnyNode::Activity(activity_id));
+                            }
+                            Some(AnyNode::Barrier(barrier_id)) => {
+                                let activity_id =
+                                    self.add_activity_node(Graph::new_random_activity(pos));
+                                self.toggle_barrier_connection(activity_id, barrier_id, false);
+                                self.currently_connecting_from =
+                                    Some(AnyNode::Activity(activity_id));
+                            }
                             Some(AnyNode::Mutex(mutex_id)) => {
                                 let activity_id =
                                     self.add_activity_node(Graph::new_random_activity(pos));
@@ -869,39 +2993,68 @@ impl Graph {
                 }
 
                 // draw connection preview
-                if let Some(pointer_pos) = ui.input(|i| i.pointer.latest_pos()) {
-                    match self.currently_connecting_from {
-                        Some(AnyNode::Mutex(id)) => {
-                            if let Some(node) = self.mutex_nodes.get(&id) {
-                                connection::Connection::draw_arrow(
-                                    ui,
-                                    node.pos,
-                                    container_transform.inverse() * pointer_pos,
-                                    connection::Color::Default,
-                                    connection::Color::Default,
-                                    0.,
-                                );
-                            }
-                        }
-                        Some(AnyNode::Activity(id)) => {
-                            if let Some(node) = self.activity_nodes.get(&id) {
-                                connection::Connection::draw_arrow(
-                                    ui,
-                                    node.pos,
-                                    container_transform.inverse() * pointer_pos,
-                                    connection::Color::Default,
-                                    connection::Color::Default,
-                                    0.,
-                                );
-                            }
-                        }
-                        None => (),
-                    };
+                if let (Some(pointer_pos), Some(from_pos)) = (
+                    ui.input(|i| i.pointer.latest_pos()),
+                    self.currently_connecting_from
+                        .and_then(|from_node| self.node_pos(from_node)),
+                ) {
+                    connection::Connection::draw_arrow(
+                        ui,
+                        from_pos,
+                        container_transform.inverse() * pointer_pos,
+                        connection::Color::Default,
+                        connection::Color::Default,
+                        0.,
+                    );
                 }
             }
         }
     }
 
+    fn node_pos(&self, node: AnyNode) -> Option<Pos2> {
+        match node {
+            AnyNode::Activity(id) => self.activity_nodes.get(&id).map(|node| node.pos),
+            AnyNode::Mutex(id) => self.mutex_nodes.get(&id).map(|node| node.pos),
+            AnyNode::Barrier(id) => self.barrier_nodes.get(&id).map(|node| node.pos),
+            AnyNode::Lock(id) => self.lock_nodes.get(&id).map(|node| node.pos),
+            AnyNode::Queue(id) => self.queue_nodes.get(&id).map(|node| node.pos),
+        }
+    }
+
+    // removes the node together with all connections to it
+    fn remove_node(&mut self, node: AnyNode) {
+        match node {
+            AnyNode::Activity(id) => {
+                self.activity_nodes.swap_remove(&id);
+                self.connections.remove(&id);
+                self.barrier_nodes
+                    .values_mut()
+                    .for_each(|barrier_node| barrier_node.remove_activity(id));
+                self.lock_nodes.values_mut().for_each(|lock_node| {
+                    lock_node.access.remove(&id);
+                });
+                self.queue_nodes
+                    .values_mut()
+                    .for_each(|queue_node| queue_node.remove_activity(id));
+            }
+            AnyNode::Mutex(id) => {
+                self.mutex_nodes.remove(&id);
+                self.connections.iter_mut().for_each(|(_, connections)| {
+                    connections.remove(&id);
+                });
+            }
+            AnyNode::Barrier(id) => {
+                self.barrier_nodes.remove(&id);
+            }
+            AnyNode::Lock(id) => {
+                self.lock_nodes.remove(&id);
+            }
+            AnyNode::Queue(id) => {
+                self.queue_nodes.remove(&id);
+            }
+        }
+    }
+
     pub fn draw(&mut self, ui: &mut egui::Ui, container_transform: egui::emath::TSTransform) {
         ui.style_mut().spacing.interact_size = egui::Vec2::ZERO;
         ui.style_mut().spacing.button_padding = egui::Vec2::ZERO;
@@ -909,14 +3062,233 @@ impl Graph {
 
         // draw
         let tick_progress = self.tick_progress;
+        let mut edited = false;
         self.do_per_connection(|c, a, m| c.draw(ui, a, m, tick_progress));
-        self.mutex_nodes
+        self.draw_barrier_connections(ui);
+        self.draw_lock_connections(ui);
+        self.draw_queue_connections(ui);
+        self.mutex_nodes.iter_mut().for_each(|(id, mutex_node)| {
+            let highlighted = self
+                .deadlock
+                .as_ref()
+                .map_or(false, |deadlock| deadlock.blocking_mutexes.contains(id));
+            let value = mutex_node.value;
+            if mutex_node.draw(ui, container_transform, highlighted) {
+                self.history
+                    .record_value_edit(history::ValueEdit::MutexValue(*id, value));
+                edited = true;
+            }
+        });
+        if self.priority_protocol != PriorityProtocol::None {
+            self.draw_mutex_holders(ui);
+        }
+        let effective_priorities = self.effective_priorities();
+        for lock_id in self.lock_nodes.keys().copied().collect::<Vec<_>>() {
+            let holders = self
+                .lock_holders(lock_id)
+                .into_iter()
+                .map(|(_, access)| access)
+                .collect::<Vec<_>>();
+            if let Some(lock_node) = self.lock_nodes.get_mut(&lock_id) {
+                lock_node.draw(ui, &holders);
+            }
+        }
+        self.queue_nodes.iter_mut().for_each(|(id, queue_node)| {
+            let highlighted = self
+                .deadlock
+                .as_ref()
+                .map_or(false, |deadlock| deadlock.blocking_queues.contains(id));
+            queue_node.draw(ui, highlighted)
+        });
+        self.barrier_nodes
             .iter_mut()
-            .for_each(|n| n.1.draw(ui, container_transform));
+            .for_each(|(id, barrier_node)| {
+                let highlighted = self
+                    .deadlock
+                    .as_ref()
+                    .map_or(false, |deadlock| deadlock.blocking_barriers.contains(id));
+                edited |= barrier_node.draw(ui, container_transform, highlighted)
+            });
         self.activity_nodes
             .iter_mut()
-            .for_each(|(_, activity_node)| {
-                activity_node.draw(ui, container_transform, tick_progress)
+            .for_each(|(id, activity_node)| {
+                let highlighted = self
+                    .deadlock
+                    .as_ref()
+                    .map_or(false, |deadlock| deadlock.blocked_activities.contains(id));
+                let remaining_duration = activity_node.remaining_duration;
+                edited |= activity_node.draw(
+                    ui,
+                    container_transform,
+                    tick_progress,
+                    highlighted,
+                    effective_priorities.get(id).copied(),
+                );
+                if activity_node.remaining_duration != remaining_duration {
+                    self.history
+                        .record_value_edit(history::ValueEdit::RemainingDuration(
+                            *id,
+                            remaining_duration,
+                        ));
+                }
             });
+        if edited {
+            self.history.mark_edited();
+        }
+    }
+
+    // below the mutexes that are currently held
+    fn draw_mutex_holders(&self, ui: &egui::Ui) {
+        for (mutex_id, mutex_node) in &self.mutex_nodes {
+            let holders = self.mutex_holders(*mutex_id);
+            if holders.is_empty() {
+                continue;
+            }
+            ui.painter().text(
+                mutex_node.pos + egui::vec2(0., 24.),
+                egui::Align2::CENTER_CENTER,
+                format!(
+                    "held by {}",
+                    holders
+                        .iter()
+                        .map(|id| id.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+                egui::FontId::proportional(12.),
+                egui::Color32::LIGHT_GRAY,
+            );
+        }
+    }
+
+    // producers are labeled with what they push, consumers with what they received last
+    fn draw_queue_connections(&self, ui: &egui::Ui) {
+        for queue_node in self.queue_nodes.values() {
+            for (activity_id, label) in &queue_node.producers {
+                let Some(activity_node) = self.activity_nodes.get(activity_id) else {
+                    continue;
+                };
+                let color = match activity_node.is_running() {
+                    true => connection::Color::Active,
+                    false => connection::Color::Default,
+                };
+                connection::Connection::draw_arrow(
+                    ui,
+                    activity_node.pos,
+                    queue_node.pos,
+                    connection::Color::Default,
+                    color,
+                    0.,
+                );
+                connection::Connection::draw_colored_label(
+                    ui,
+                    activity_node.pos,
+                    queue_node.pos,
+                    label,
+                    queue_node::label_color(label),
+                );
+            }
+            for activity_id in &queue_node.consumers {
+                let Some(activity_node) = self.activity_nodes.get(activity_id) else {
+                    continue;
+                };
+                let color = match queue_node.tokens.is_empty() {
+                    true => connection::Color::Default,
+                    false => connection::Color::Active,
+                };
+                connection::Connection::draw_arrow(
+                    ui,
+                    queue_node.pos,
+                    activity_node.pos,
+                    color,
+                    color,
+                    0.,
+                );
+                if let Some(label) = queue_node.received.get(activity_id) {
+                    connection::Connection::draw_colored_label(
+                        ui,
+                        queue_node.pos,
+                        activity_node.pos,
+                        label,
+                        queue_node::label_color(label),
+                    );
+                }
+            }
+        }
+    }
+
+    // arrows from the lock to its holders are active, shared ones are labeled
+    fn draw_lock_connections(&self, ui: &egui::Ui) {
+        for (lock_id, lock_node) in &self.lock_nodes {
+            let holders = self.lock_holders(*lock_id);
+            for (activity_id, access) in &lock_node.access {
+                let Some(activity_node) = self.activity_nodes.get(activity_id) else {
+                    continue;
+                };
+                let color = match holders
+                    .iter()
+                    .any(|(holder_id, _)| holder_id == activity_id)
+                {
+                    true => connection::Color::Active,
+                    false => connection::Color::Default,
+                };
+                connection::Connection::draw_arrow(
+                    ui,
+                    lock_node.pos,
+                    activity_node.pos,
+                    color,
+                    color,
+                    0.,
+                );
+                if *access == LockAccess::Shared {
+                    connection::Connection::draw_label(
+                        ui,
+                        lock_node.pos,
+                        activity_node.pos,
+                        "shared",
+                    );
+                }
+            }
+        }
+    }
+
+    // arrows into the barrier are active while the activity runs,
+    // arrows out of it from the release of the barrier until the activity starts
+    fn draw_barrier_connections(&self, ui: &egui::Ui) {
+        for barrier_node in self.barrier_nodes.values() {
+            for (activity_ids, arriving) in [
+                (&barrier_node.arriving, true),
+                (&barrier_node.waiting, false),
+            ] {
+                for activity_id in activity_ids {
+                    let Some(activity_node) = self.activity_nodes.get(activity_id) else {
+                        continue;
+                    };
+                    let (from_point, to_point, active) = match arriving {
+                        true => (
+                            activity_node.pos,
+                            barrier_node.pos,
+                            activity_node.is_running(),
+                        ),
+                        false => (
+                            barrier_node.pos,
+                            activity_node.pos,
+                            barrier_node.has_pass(*activity_id),
+                        ),
+                    };
+                    connection::Connection::draw_arrow(
+                        ui,
+                        from_point,
+                        to_point,
+                        connection::Color::Default,
+                        match active {
+                            true => connection::Color::Active,
+                            false => connection::Color::Default,
+                        },
+                        0.,
+                    );
+                }
+            }
+        }
     }
 }
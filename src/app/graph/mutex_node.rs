@@ -1,7 +1,22 @@
+use super::timeline::MutexState;
+
 #[derive(Default, serde::Deserialize, serde::Serialize)]
 pub struct MutexNode {
     pub pos: egui::Pos2,
     pub value: u32,
+    // producers block instead of exceeding it, None means unbounded
+    #[serde(default)]
+    pub capacity: Option<u32>,
+    // gains a unit every period ticks, 0 means never
+    #[serde(default)]
+    pub period: u32,
+    // tick of the first unit
+    #[serde(default)]
+    pub phase: u32,
+
+    // tasks that took a unit in one activity and give it back in a later one
+    #[serde(default)]
+    pub(super) held_by: std::collections::BTreeSet<String>,
 
     #[serde(skip)]
     response_outer_id: Option<egui::Id>,
@@ -14,6 +29,10 @@ impl Clone for MutexNode {
         Self {
             pos: self.pos,
             value: self.value,
+            capacity: self.capacity,
+            period: self.period,
+            phase: self.phase,
+            held_by: self.held_by.clone(),
             response_outer_id: None,
             response_value_id: None,
         }
@@ -28,6 +47,33 @@ impl MutexNode {
         }
     }
 
+    pub fn is_full(&self) -> bool {
+        self.capacity
+            .map_or(false, |capacity| self.value >= capacity)
+    }
+
+    pub fn is_periodic(&self) -> bool {
+        self.period > 0
+    }
+
+    pub fn is_refilled_in(&self, tick: u64) -> bool {
+        self.is_periodic()
+            && tick >= self.phase as u64
+            && (tick - self.phase as u64) % self.period as u64 == 0
+    }
+
+    pub fn simulation_state(&self) -> MutexState {
+        MutexState {
+            value: self.value,
+            held_by: self.held_by.clone(),
+        }
+    }
+
+    pub fn restore_simulation_state(&mut self, state: &MutexState) {
+        self.value = state.value;
+        self.held_by = state.held_by.clone();
+    }
+
     pub fn interact(&mut self, ui: &egui::Ui) -> Option<egui::Response> {
         if let (Some(Some(response_outer)), Some(Some(response_value))) = (
             self.response_outer_id
@@ -48,7 +94,12 @@ impl MutexNode {
         }
     }
 
-    pub fn draw(&mut self, ui: &mut egui::Ui, container_transform: egui::emath::TSTransform) {
+    pub fn draw(
+        &mut self,
+        ui: &mut egui::Ui,
+        container_transform: egui::emath::TSTransform,
+        highlighted: bool,
+    ) -> bool {
         let style = ui.ctx().style().visuals.widgets.inactive;
 
         let mut ui = ui.child_ui(ui.max_rect(), *ui.layout());
@@ -57,21 +108,39 @@ impl MutexNode {
         let outer_rect = egui::Rect::from_center_size(self.pos, egui::Vec2::splat(30.));
 
         let mut stroke = style.fg_stroke;
-        if self.value != 0 {
+        if self.is_full() {
+            stroke.color = egui::Color32::from_rgb(0, 150, 255);
+            stroke.width = 1.5;
+        } else if self.value != 0 {
             stroke.color = egui::Color32::GREEN;
             stroke.width = 1.5;
         }
         ui.painter().rect_filled(outer_rect, 0., style.bg_fill);
         ui.painter().rect_stroke(outer_rect, 0., stroke);
+        if highlighted {
+            ui.painter().rect_stroke(
+                outer_rect.expand(4.),
+                0.,
+                egui::Stroke::new(2., egui::Color32::YELLOW),
+            );
+        }
         let response_outer = ui.allocate_rect(outer_rect, egui::Sense::click_and_drag());
         self.response_outer_id = Some(response_outer.id);
 
+        let (value_size, suffix) = match self.capacity {
+            Some(capacity) => (egui::vec2(28., 15.), format!("/{}", capacity)),
+            None => (egui::Vec2::splat(15.), String::new()),
+        };
         let response_value = ui.put(
-            egui::Rect::from_center_size(self.pos, egui::Vec2::splat(15.)),
+            egui::Rect::from_center_size(self.pos, value_size),
             egui::DragValue::new(&mut self.value)
                 .update_while_editing(false)
+                .clamp_range(0..=self.capacity.unwrap_or(u32::MAX))
+                .suffix(suffix)
                 .speed(container_transform.scaling * 0.05),
         );
         self.response_value_id = Some(response_value.id);
+
+        response_value.changed()
     }
 }
@@ -0,0 +1,437 @@
+use super::{
+    connection::{Direction, InputKind, OutputKind},
+    distribution::{self, DurationDistribution},
+    history, queue_node, ActivityNodeId, Graph, LockAccess, MAX_THREE_DIGIT_NUMBER,
+};
+
+impl Graph {
+    pub fn task_properties_ui(&mut self, ui: &mut egui::Ui) {
+        let mut activity_ids = self.activity_nodes.keys().copied().collect::<Vec<_>>();
+        activity_ids.sort();
+
+        let mut changed = false;
+        egui::ScrollArea::both().show(ui, |ui| {
+            egui::Grid::new("task_properties")
+                .striped(true)
+                .show(ui, |ui| {
+                    headers_ui(
+                        ui,
+                        &[
+                            ("ID", None),
+                            ("Task", None),
+                            ("Activity", None),
+                            ("Priority", None),
+                            ("Duration", None),
+                            ("Period", Some("released every period ticks, 0 means none")),
+                            ("Phase", Some("the tick of the first release")),
+                            (
+                                "Deadline",
+                                Some("counted from the release of a run, 0 means none"),
+                            ),
+                            (
+                                "Duration Distribution",
+                                Some("chosen for every run, based on the seed"),
+                            ),
+                        ],
+                    );
+
+                    for id in activity_ids {
+                        let activity_node = &mut self.activity_nodes[&id];
+                        ui.label(id.to_string());
+                        ui.label(&activity_node.task_name);
+                        ui.label(&activity_node.activity_name);
+                        for (value, min) in [
+                            (&mut activity_node.priority, 0),
+                            (&mut activity_node.duration, 1),
+                            (&mut activity_node.period, 0),
+                            (&mut activity_node.phase, 0),
+                            (&mut activity_node.deadline, 0),
+                        ] {
+                            changed |= ui
+                                .add(
+                                    egui::DragValue::new(value)
+                                        .update_while_editing(false)
+                                        .clamp_range(min..=MAX_THREE_DIGIT_NUMBER),
+                                )
+                                .changed();
+                        }
+                        changed |= distribution_ui(
+                            ui,
+                            id,
+                            &mut activity_node.duration_distribution,
+                            activity_node.duration,
+                        );
+                        ui.end_row();
+                    }
+                });
+
+            ui.separator();
+            egui::Grid::new("connection_weights")
+                .striped(true)
+                .show(ui, |ui| {
+                    headers_ui(
+                        ui,
+                        &[
+                            ("Task ID", None),
+                            ("Mutex ID", None),
+                            (
+                                "Input",
+                                Some(
+                                    "inhibitors require less than their weight, \
+                                    read inputs at least their weight, \
+                                    neither of them takes anything from the mutex",
+                                ),
+                            ),
+                            ("Input Weight", Some("units consumed at once")),
+                            (
+                                "Output",
+                                Some("a reset sets the mutex to zero when the task finishes"),
+                            ),
+                            ("Output Weight", Some("units produced at once")),
+                        ],
+                    );
+
+                    let mut connection_ids = self
+                        .connections
+                        .iter()
+                        .flat_map(|(activity_id, activity_connections)| {
+                            activity_connections
+                                .keys()
+                                .map(move |mutex_id| (*activity_id, *mutex_id))
+                        })
+                        .collect::<Vec<_>>();
+                    connection_ids.sort();
+
+                    for (activity_id, mutex_id) in connection_ids {
+                        if let Some(connection) = self.get_connection_mut(activity_id, mutex_id) {
+                            ui.label(activity_id.to_string());
+                            ui.label(mutex_id.to_string());
+                            let direction = connection.get_direction();
+                            let has_input = direction != Direction::ActivityToMutex;
+                            let has_output = direction != Direction::MutexToActivity;
+
+                            let mut input_kind = connection.get_input_kind();
+                            if kind_ui(
+                                ui,
+                                ("input_kind", activity_id, mutex_id),
+                                has_input,
+                                &mut input_kind,
+                                &InputKind::ALL,
+                                InputKind::name,
+                            ) {
+                                connection.set_input_kind(input_kind);
+                                changed = true;
+                            }
+                            let mut input_weight = connection.get_input_weight();
+                            changed |= weight_ui(ui, has_input, &mut input_weight);
+                            connection.set_input_weight(input_weight);
+
+                            let mut output_kind = connection.get_output_kind();
+                            if kind_ui(
+                                ui,
+                                ("output_kind", activity_id, mutex_id),
+                                has_output,
+                                &mut output_kind,
+                                &OutputKind::ALL,
+                                OutputKind::name,
+                            ) {
+                                connection.set_output_kind(output_kind);
+                                changed = true;
+                            }
+                            // a reset does not depend on the weight
+                            let mut output_weight = connection.get_output_weight();
+                            changed |= weight_ui(
+                                ui,
+                                has_output && output_kind != OutputKind::Reset,
+                                &mut output_weight,
+                            );
+                            connection.set_output_weight(output_weight);
+                            ui.end_row();
+                        }
+                    }
+                });
+
+            ui.separator();
+            egui::Grid::new("mutex_settings")
+                .striped(true)
+                .show(ui, |ui| {
+                    headers_ui(
+                        ui,
+                        &[
+                            ("Mutex ID", None),
+                            (
+                                "Capacity",
+                                Some("tasks do not start while an output mutex is too full"),
+                            ),
+                            (
+                                "Period",
+                                Some("gains a unit every period ticks, 0 means never"),
+                            ),
+                            ("Phase", Some("the tick of the first unit")),
+                        ],
+                    );
+
+                    let mut mutex_ids = self.mutex_nodes.keys().copied().collect::<Vec<_>>();
+                    mutex_ids.sort();
+
+                    for id in mutex_ids {
+                        if let Some(mutex_node) = self.mutex_nodes.get_mut(&id) {
+                            ui.label(id.to_string());
+                            ui.horizontal(|ui| {
+                                let mut limited = mutex_node.capacity.is_some();
+                                if ui.checkbox(&mut limited, "").changed() {
+                                    mutex_node.capacity =
+                                        limited.then_some(mutex_node.value.max(1));
+                                    changed = true;
+                                }
+                                if let Some(capacity) = &mut mutex_node.capacity {
+                                    changed |= ui
+                                        .add(
+                                            egui::DragValue::new(capacity)
+                                                .update_while_editing(false)
+                                                .clamp_range(1..=MAX_THREE_DIGIT_NUMBER),
+                                        )
+                                        .changed();
+                                    if mutex_node.value > *capacity {
+                                        self.history.record_value_edit(
+                                            history::ValueEdit::MutexValue(id, mutex_node.value),
+                                        );
+                                        mutex_node.value = *capacity;
+                                    }
+                                }
+                            });
+                            for value in [&mut mutex_node.period, &mut mutex_node.phase] {
+                                changed |= ui
+                                    .add(
+                                        egui::DragValue::new(value)
+                                            .update_while_editing(false)
+                                            .clamp_range(0..=MAX_THREE_DIGIT_NUMBER),
+                                    )
+                                    .changed();
+                            }
+                            ui.end_row();
+                        }
+                    }
+                });
+
+            ui.separator();
+            egui::Grid::new("lock_access").striped(true).show(ui, |ui| {
+                headers_ui(
+                    ui,
+                    &[
+                        ("Lock ID", None),
+                        ("Task ID", None),
+                        (
+                            "Access",
+                            Some(
+                                "held while running, shared access only excludes exclusive access",
+                            ),
+                        ),
+                    ],
+                );
+
+                let mut lock_ids = self.lock_nodes.keys().copied().collect::<Vec<_>>();
+                lock_ids.sort();
+
+                for lock_id in lock_ids {
+                    if let Some(lock_node) = self.lock_nodes.get_mut(&lock_id) {
+                        for (activity_id, access) in &mut lock_node.access {
+                            ui.label(lock_id.to_string());
+                            ui.label(activity_id.to_string());
+                            changed |= kind_ui(
+                                ui,
+                                ("lock_access", lock_id, *activity_id),
+                                true,
+                                access,
+                                &LockAccess::ALL,
+                                LockAccess::name,
+                            );
+                            ui.end_row();
+                        }
+                    }
+                }
+            });
+
+            ui.separator();
+            egui::Grid::new("queue_labels")
+                .striped(true)
+                .show(ui, |ui| {
+                    headers_ui(
+                        ui,
+                        &[
+                            ("Queue ID", None),
+                            ("Task ID", None),
+                            (
+                                "Label",
+                                Some(
+                                    "pushed when the producer finishes, consumers pop one to start",
+                                ),
+                            ),
+                        ],
+                    );
+
+                    let mut queue_ids = self.queue_nodes.keys().copied().collect::<Vec<_>>();
+                    queue_ids.sort();
+
+                    for queue_id in queue_ids {
+                        if let Some(queue_node) = self.queue_nodes.get_mut(&queue_id) {
+                            for (activity_id, label) in &mut queue_node.producers {
+                                ui.label(queue_id.to_string());
+                                ui.label(activity_id.to_string());
+                                // keep the text while editing, even if it is empty
+                                let id = ui.id().with(("queue_label", queue_id, *activity_id));
+                                let mut text = match ui.memory(|memory| memory.has_focus(id)) {
+                                    true => ui
+                                        .data_mut(|data| data.get_temp::<String>(id))
+                                        .unwrap_or_default(),
+                                    false => label.clone(),
+                                };
+                                if ui
+                                    .add(
+                                        egui::TextEdit::singleline(&mut text)
+                                            .id(id)
+                                            .desired_width(100.),
+                                    )
+                                    .changed()
+                                {
+                                    let new_label = queue_node::sanitize_label(&text);
+                                    if new_label != *label {
+                                        *label = new_label;
+                                        changed = true;
+                                    }
+                                }
+                                ui.data_mut(|data| data.insert_temp(id, text));
+                                ui.end_row();
+                            }
+                        }
+                    }
+                });
+        });
+
+        if changed {
+            self.history.mark_edited();
+        }
+    }
+}
+
+// a row of grid headers, some of them explain their column when hovered
+fn headers_ui(ui: &mut egui::Ui, headers: &[(&str, Option<&str>)]) {
+    for (header, hover_text) in headers {
+        let response = ui.label(*header);
+        if let Some(hover_text) = hover_text {
+            response.on_hover_text(*hover_text);
+        }
+    }
+    ui.end_row();
+}
+
+fn kind_ui<K: Copy + PartialEq>(
+    ui: &mut egui::Ui,
+    id_source: impl std::hash::Hash,
+    enabled: bool,
+    kind: &mut K,
+    all: &[K],
+    name: fn(&K) -> &'static str,
+) -> bool {
+    let mut changed = false;
+    ui.add_enabled_ui(enabled, |ui| {
+        egui::ComboBox::from_id_source(id_source)
+            .selected_text(name(kind))
+            .show_ui(ui, |ui| {
+                for other_kind in all {
+                    if ui
+                        .selectable_label(*kind == *other_kind, name(other_kind))
+                        .clicked()
+                        && *kind != *other_kind
+                    {
+                        *kind = *other_kind;
+                        changed = true;
+                    }
+                }
+            });
+    });
+    changed
+}
+
+fn weight_ui(ui: &mut egui::Ui, enabled: bool, weight: &mut u32) -> bool {
+    ui.add_enabled(
+        enabled,
+        egui::DragValue::new(weight)
+            .update_while_editing(false)
+            .clamp_range(1..=MAX_THREE_DIGIT_NUMBER),
+    )
+    .changed()
+}
+
+fn distribution_ui(
+    ui: &mut egui::Ui,
+    activity_id: ActivityNodeId,
+    duration_distribution: &mut DurationDistribution,
+    duration: u32,
+) -> bool {
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        egui::ComboBox::from_id_source(("duration_distribution", activity_id))
+            .selected_text(duration_distribution.name())
+            .show_ui(ui, |ui| {
+                for name in DurationDistribution::NAMES {
+                    if ui
+                        .selectable_label(duration_distribution.name() == name, name)
+                        .clicked()
+                        && duration_distribution.name() != name
+                    {
+                        if let Some(new_distribution) =
+                            DurationDistribution::from_name(name, duration)
+                        {
+                            *duration_distribution = new_distribution;
+                            changed = true;
+                        }
+                    }
+                }
+            });
+
+        let mut range_ui = |ui: &mut egui::Ui, low: &mut u32, high: &mut u32| {
+            for value in [low, high] {
+                changed |= ui
+                    .add(
+                        egui::DragValue::new(value)
+                            .update_while_editing(false)
+                            .clamp_range(1..=MAX_THREE_DIGIT_NUMBER),
+                    )
+                    .changed();
+            }
+        };
+        match duration_distribution {
+            DurationDistribution::Fixed => {}
+            DurationDistribution::Uniform { min, max } => range_ui(ui, min, max),
+            DurationDistribution::BestWorstCase { best, worst } => range_ui(ui, best, worst),
+            DurationDistribution::Discrete(options) => {
+                // keep the text while editing, even if it can not be parsed yet
+                let id = ui.id().with(("duration_options", activity_id));
+                let mut text = match ui.memory(|memory| memory.has_focus(id)) {
+                    true => ui
+                        .data_mut(|data| data.get_temp::<String>(id))
+                        .unwrap_or_default(),
+                    false => distribution::options_to_text(options),
+                };
+                let response = ui
+                    .add(
+                        egui::TextEdit::singleline(&mut text)
+                            .id(id)
+                            .desired_width(100.),
+                    )
+                    .on_hover_text("duration:weight, separated by spaces");
+                if response.changed() {
+                    if let Some(new_options) = distribution::options_from_text(&text) {
+                        if new_options != *options {
+                            *options = new_options;
+                            changed = true;
+                        }
+                    }
+                }
+                ui.data_mut(|data| data.insert_temp(id, text));
+            }
+        }
+    });
+    changed
+}
@@ -0,0 +1,173 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use super::{timeline::QueueState, ActivityNodeId};
+
+const DEFAULT_LABEL: &str = "msg";
+const MAX_VISIBLE_TOKENS: usize = 4;
+
+// labels are single words, so they can be stored as a space separated list
+pub fn sanitize_label(label: &str) -> String {
+    let label = label
+        .chars()
+        .filter(|c| !c.is_whitespace() && !matches!(c, ';' | '='))
+        .collect::<String>();
+    match label.is_empty() {
+        true => DEFAULT_LABEL.to_string(),
+        false => label,
+    }
+}
+
+// the same label always gets the same color
+pub fn label_color(label: &str) -> egui::Color32 {
+    use std::hash::{Hash as _, Hasher as _};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    label.hash(&mut hasher);
+    let hue = (hasher.finish() % 360) as f32 / 360.;
+    egui::ecolor::Hsva::new(hue, 0.6, 0.9, 1.).into()
+}
+
+#[derive(Default, serde::Deserialize, serde::Serialize)]
+pub struct QueueNode {
+    pub pos: egui::Pos2,
+    // the front of the queue comes first
+    pub tokens: VecDeque<String>,
+    // activities that push their label when they finish
+    pub producers: BTreeMap<ActivityNodeId, String>,
+    // activities that pop a token when they start
+    pub consumers: BTreeSet<ActivityNodeId>,
+    // the label each consumer popped last
+    pub received: BTreeMap<ActivityNodeId, String>,
+
+    #[serde(skip)]
+    response_outer_id: Option<egui::Id>,
+}
+
+impl Clone for QueueNode {
+    fn clone(&self) -> Self {
+        Self {
+            pos: self.pos,
+            tokens: self.tokens.clone(),
+            producers: self.producers.clone(),
+            consumers: self.consumers.clone(),
+            received: self.received.clone(),
+            response_outer_id: None,
+        }
+    }
+}
+
+impl QueueNode {
+    pub fn new(pos: egui::Pos2) -> Self {
+        Self {
+            pos,
+            ..Default::default()
+        }
+    }
+
+    pub fn push(&mut self, activity_id: ActivityNodeId) {
+        if let Some(label) = self.producers.get(&activity_id) {
+            self.tokens.push_back(label.clone());
+        }
+    }
+
+    pub fn pop(&mut self, activity_id: ActivityNodeId) {
+        if let Some(label) = self.tokens.pop_front() {
+            self.received.insert(activity_id, label);
+        }
+    }
+
+    pub fn toggle_producer(&mut self, activity_id: ActivityNodeId) {
+        if self.producers.remove(&activity_id).is_none() {
+            self.producers
+                .insert(activity_id, DEFAULT_LABEL.to_string());
+        }
+    }
+
+    pub fn toggle_consumer(&mut self, activity_id: ActivityNodeId) {
+        if !self.consumers.remove(&activity_id) {
+            self.consumers.insert(activity_id);
+        }
+        self.received.remove(&activity_id);
+    }
+
+    pub fn remove_activity(&mut self, activity_id: ActivityNodeId) {
+        self.producers.remove(&activity_id);
+        self.consumers.remove(&activity_id);
+        self.received.remove(&activity_id);
+    }
+
+    pub fn simulation_state(&self) -> QueueState {
+        QueueState {
+            tokens: self.tokens.clone(),
+            received: self.received.clone(),
+        }
+    }
+
+    pub fn restore_simulation_state(&mut self, state: &QueueState) {
+        self.tokens = state.tokens.clone();
+        self.received = state.received.clone();
+    }
+
+    pub fn interact(&mut self, ui: &egui::Ui) -> Option<egui::Response> {
+        if let Some(Some(response_outer)) = self
+            .response_outer_id
+            .map(|response_outer_id| ui.ctx().read_response(response_outer_id))
+        {
+            if !ui.ctx().input(|i| i.pointer.secondary_down())
+                && (response_outer.dragged() || response_outer.drag_stopped())
+            {
+                self.pos += response_outer.drag_delta();
+            }
+
+            Some(response_outer)
+        } else {
+            None
+        }
+    }
+
+    pub fn draw(&mut self, ui: &mut egui::Ui, highlighted: bool) {
+        let style = ui.ctx().style().visuals.widgets.inactive;
+
+        let mut ui = ui.child_ui(ui.max_rect(), *ui.layout());
+
+        // a pipe with the tokens in it, the front of the queue on the right
+        let outer_rect = egui::Rect::from_center_size(self.pos, egui::vec2(64., 24.));
+
+        let mut stroke = style.fg_stroke;
+        if !self.tokens.is_empty() {
+            stroke.color = egui::Color32::GREEN;
+            stroke.width = 1.5;
+        }
+        ui.painter().rect_filled(outer_rect, 12., style.bg_fill);
+        ui.painter().rect_stroke(outer_rect, 12., stroke);
+        for (i, label) in self.tokens.iter().take(MAX_VISIBLE_TOKENS).enumerate() {
+            ui.painter().circle_filled(
+                outer_rect.right_center() - egui::vec2(12. + i as f32 * 13., 0.),
+                5.,
+                label_color(label),
+            );
+        }
+        if self.tokens.len() > MAX_VISIBLE_TOKENS {
+            ui.painter().text(
+                outer_rect.center_bottom() + egui::vec2(0., 8.),
+                egui::Align2::CENTER_CENTER,
+                format!("{} tokens", self.tokens.len()),
+                egui::FontId::proportional(12.),
+                egui::Color32::LIGHT_GRAY,
+            );
+        }
+        if highlighted {
+            ui.painter().rect_stroke(
+                outer_rect.expand(4.),
+                16.,
+                egui::Stroke::new(2., egui::Color32::YELLOW),
+            );
+        }
+        let response_outer = ui.allocate_rect(outer_rect, egui::Sense::click_and_drag());
+        let response_outer = match self.tokens.is_empty() {
+            true => response_outer.on_hover_text("empty"),
+            false => response_outer
+                .on_hover_text(self.tokens.iter().cloned().collect::<Vec<_>>().join(" ")),
+        };
+        self.response_outer_id = Some(response_outer.id);
+    }
+}
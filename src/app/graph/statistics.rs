@@ -0,0 +1,195 @@
+use std::collections::BTreeMap;
+
+use super::{gantt::ActivityStatus, ActivityNodeId, Graph, MutexNodeId};
+
+#[derive(Clone, Default)]
+pub struct ActivityStatistics {
+    pub completed_runs: u32,
+    pub running_ticks: u64,
+    pub waiting_ticks: u64,
+    pub waits: u32,
+    pub current_wait: u64,
+    pub longest_wait: u64,
+    pub deadline_misses: u32,
+}
+
+impl ActivityStatistics {
+    pub fn average_wait(&self) -> f32 {
+        match self.waits {
+            0 => 0.,
+            waits => self.waiting_ticks as f32 / waits as f32,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct MutexStatistics {
+    pub min: u32,
+    pub max: u32,
+    value_sum: u64,
+    samples: u64,
+}
+
+impl MutexStatistics {
+    pub fn average(&self) -> f32 {
+        match self.samples {
+            0 => 0.,
+            samples => self.value_sum as f32 / samples as f32,
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct Statistics {
+    pub ticks: u64,
+    pub activities: BTreeMap<ActivityNodeId, ActivityStatistics>,
+    pub mutexes: BTreeMap<MutexNodeId, MutexStatistics>,
+}
+
+impl Statistics {
+    // only released activities wait, idle ones have nothing to do
+    pub fn record_activity(&mut self, activity_id: ActivityNodeId, status: ActivityStatus) {
+        let statistics = self.activities.entry(activity_id).or_default();
+        match status {
+            ActivityStatus::Running => {
+                statistics.running_ticks += 1;
+                statistics.current_wait = 0;
+            }
+            ActivityStatus::Preempted | ActivityStatus::Waiting => {
+                if statistics.current_wait == 0 {
+                    statistics.waits += 1;
+                }
+                statistics.current_wait += 1;
+                statistics.waiting_ticks += 1;
+                statistics.longest_wait = statistics.longest_wait.max(statistics.current_wait);
+            }
+            ActivityStatus::Idle => statistics.current_wait = 0,
+        }
+    }
+
+    pub fn record_completed_run(&mut self, activity_id: ActivityNodeId) {
+        self.activities
+            .entry(activity_id)
+            .or_default()
+            .completed_runs += 1;
+    }
+
+    pub fn record_deadline_miss(&mut self, activity_id: ActivityNodeId) {
+        self.activities
+            .entry(activity_id)
+            .or_default()
+            .deadline_misses += 1;
+    }
+
+    pub fn record_mutex(&mut self, mutex_id: MutexNodeId, value: u32) {
+        let statistics = self.mutexes.entry(mutex_id).or_insert(MutexStatistics {
+            min: value,
+            max: value,
+            value_sum: 0,
+            samples: 0,
+        });
+        statistics.min = statistics.min.min(value);
+        statistics.max = statistics.max.max(value);
+        statistics.value_sum += value as u64;
+        statistics.samples += 1;
+    }
+}
+
+impl Graph {
+    pub fn reset_statistics(&mut self) {
+        self.statistics = Statistics::default();
+    }
+
+    pub(super) fn record_mutex_statistics(&mut self) {
+        for (id, mutex_node) in &self.mutex_nodes {
+            self.statistics.record_mutex(*id, mutex_node.value);
+        }
+    }
+
+    pub fn statistics_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(format!("{} ticks recorded", self.statistics.ticks));
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui.button("Reset").clicked() {
+                    self.reset_statistics();
+                }
+            });
+        });
+
+        ui.heading("Activities");
+        egui::Grid::new("activity_statistics")
+            .striped(true)
+            .show(ui, |ui| {
+                for header in [
+                    "Activity",
+                    "Runs",
+                    "Running",
+                    "Total Wait",
+                    "Avg. Wait",
+                    "Longest Wait",
+                    "Deadline Misses",
+                ] {
+                    ui.label(header);
+                }
+                ui.end_row();
+
+                let default_statistics = ActivityStatistics::default();
+                let mut activity_ids = self.activity_nodes.keys().copied().collect::<Vec<_>>();
+                activity_ids.sort();
+                for id in activity_ids {
+                    let activity_node = &self.activity_nodes[&id];
+                    let statistics = self
+                        .statistics
+                        .activities
+                        .get(&id)
+                        .unwrap_or(&default_statistics);
+                    ui.label(format!(
+                        "{}: {}",
+                        activity_node.task_name, activity_node.activity_name
+                    ));
+                    ui.label(statistics.completed_runs.to_string());
+                    ui.label(match self.statistics.ticks {
+                        0 => "-".to_string(),
+                        ticks => format!(
+                            "{:.0}%",
+                            statistics.running_ticks as f32 / ticks as f32 * 100.
+                        ),
+                    });
+                    ui.label(statistics.waiting_ticks.to_string());
+                    ui.label(format!("{:.1}", statistics.average_wait()));
+                    ui.label(statistics.longest_wait.to_string());
+                    ui.label(statistics.deadline_misses.to_string());
+                    ui.end_row();
+                }
+            });
+
+        ui.heading("Mutexes");
+        egui::Grid::new("mutex_statistics")
+            .striped(true)
+            .show(ui, |ui| {
+                for header in ["Mutex", "Min", "Max", "Avg."] {
+                    ui.label(header);
+                }
+                ui.end_row();
+
+                let mut mutex_ids = self.mutex_nodes.keys().copied().collect::<Vec<_>>();
+                mutex_ids.sort();
+                for id in mutex_ids {
+                    ui.label(id.to_string());
+                    match self.statistics.mutexes.get(&id) {
+                        Some(statistics) => {
+                            ui.label(statistics.min.to_string());
+                            ui.label(statistics.max.to_string());
+                            ui.label(format!("{:.2}", statistics.average()));
+                        }
+                        None => {
+                            ui.label("-");
+                            ui.label("-");
+                            ui.label("-");
+                        }
+                    }
+                    ui.end_row();
+                }
+            });
+    }
+}
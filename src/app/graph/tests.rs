@@ -0,0 +1,1336 @@
+use super::{
+    distribution::DurationDistribution, ActivityNodeId, BarrierNodeId, Graph, LockAccess,
+    LockNodeId, MutexNodeId, PriorityProtocol, QueueNodeId,
+};
+
+fn load(csv: &str) -> Graph {
+    Graph::from_csv(csv).unwrap()
+}
+
+fn remaining_duration(graph: &Graph, id: usize) -> u32 {
+    graph
+        .get_activity_node(ActivityNodeId(id))
+        .unwrap()
+        .remaining_duration
+}
+
+fn mutex_value(graph: &Graph, id: usize) -> u32 {
+    graph.get_mutex_node(MutexNodeId(id)).unwrap().value
+}
+
+// A needs the token of B and the other way around, A starts with it
+const PING_PONG: &str = "\
+Task;0;0;0;A;a;0;2;0;0
+Task;0;0;1;B;b;0;1;0;1
+Mutex;0;0;0;0;1
+Mutex;0;0;1;1;0
+";
+
+#[test]
+fn step_starts_ready_activities_and_counts_down() {
+    let mut graph = load(PING_PONG);
+    graph.step();
+    assert_eq!(remaining_duration(&graph, 0), 1);
+    assert_eq!(remaining_duration(&graph, 1), 0);
+    assert_eq!(mutex_value(&graph, 1), 0);
+    assert_eq!(mutex_value(&graph, 0), 0);
+
+    // the output is only produced once the activity finished
+    graph.step();
+    assert_eq!(remaining_duration(&graph, 0), 0);
+    assert_eq!(mutex_value(&graph, 0), 1);
+}
+
+#[test]
+fn run_alternates_between_activities() {
+    let mut graph = load(PING_PONG);
+    graph.run(4);
+    // B used the token of A and gave it back, so A started again
+    assert_eq!(remaining_duration(&graph, 0), 1);
+    assert_eq!(mutex_value(&graph, 0), 0);
+    assert_eq!(mutex_value(&graph, 1), 0);
+    graph.run(5);
+    assert_eq!(remaining_duration(&graph, 0), 0);
+    assert_eq!(remaining_duration(&graph, 1), 0);
+    assert_eq!(mutex_value(&graph, 0), 0);
+    assert_eq!(mutex_value(&graph, 1), 1);
+}
+
+// three activities with the same priority compete for a single token
+const TIE: &str = "\
+Task;0;0;0;A;a;0;2;0;0
+Task;0;0;1;B;b;0;2;0;0
+Task;0;0;2;C;c;0;2;0;0
+Mutex;0;0;0;1;0;1;2
+Setting;Tie Breaking;Random
+";
+
+// the activity that got the token in each of the given number of runs
+fn tie_winners(mut graph: Graph, runs: usize) -> Vec<usize> {
+    (0..runs)
+        .map(|_| {
+            graph.step();
+            let winner = (0..3)
+                .find(|id| remaining_duration(&graph, *id) == 1)
+                .unwrap();
+            graph.step();
+            winner
+        })
+        .collect()
+}
+
+#[test]
+fn same_seed_reproduces_the_run() {
+    let run = |seed: u32| {
+        let mut graph = load(TIE);
+        graph.seed = seed;
+        tie_winners(graph, 15)
+    };
+    assert_eq!(run(7), run(7));
+    assert!((0..20).any(|seed| run(seed) != run(7)));
+}
+
+#[test]
+fn seed_and_tie_breaking_are_loaded_from_csv() {
+    let graph = load(&format!("{TIE}Setting;Seed;42\n"));
+    assert_eq!(graph.seed, 42);
+    assert!(graph.tie_breaking == super::TieBreaking::Random);
+    assert!(Graph::from_csv(&format!("{TIE}Setting;Seed;-1\n")).is_err());
+}
+
+#[test]
+fn lowest_id_tie_breaking_always_prefers_the_lowest_id() {
+    let graph = load(&TIE.replace("Random", "Lowest ID"));
+    assert_eq!(tie_winners(graph, 5), vec![0; 5]);
+}
+
+#[test]
+fn round_robin_tie_breaking_takes_turns() {
+    let graph = load(&TIE.replace("Random", "Round Robin"));
+    assert_eq!(tie_winners(graph, 6), vec![0, 1, 2, 0, 1, 2]);
+}
+
+#[test]
+fn undo_and_redo_revert_edits() {
+    let mut graph = load(PING_PONG);
+    let ctx = egui::Context::default();
+    graph.track_history(&ctx);
+    assert!(!graph.can_undo());
+
+    graph.activity_nodes[&ActivityNodeId(1)].pos.x = 100.;
+    graph.history.mark_edited();
+    assert!(graph.can_undo());
+
+    graph.undo();
+    assert_eq!(graph.activity_nodes[&ActivityNodeId(1)].pos.x, 0.);
+    assert!(!graph.can_undo());
+    assert!(graph.can_redo());
+
+    graph.redo();
+    assert_eq!(graph.activity_nodes[&ActivityNodeId(1)].pos.x, 100.);
+    assert!(graph.can_undo());
+    assert!(!graph.can_redo());
+}
+
+#[test]
+fn rewinding_restores_the_recorded_state() {
+    let state = |graph: &Graph| {
+        (
+            graph.get_current_tick(),
+            remaining_duration(graph, 0),
+            remaining_duration(graph, 1),
+            mutex_value(graph, 0),
+            mutex_value(graph, 1),
+        )
+    };
+    let mut expected = load(PING_PONG);
+    expected.run(1);
+    let after_one_tick = state(&expected);
+    expected.run(3);
+
+    let mut graph = load(PING_PONG);
+    graph.run(5);
+    assert_eq!(graph.get_timeline_range(), Some(0..=5));
+    graph.rewind_to(1);
+    assert_eq!(state(&graph), after_one_tick);
+
+    // stepping on repeats the same ticks
+    graph.run(3);
+    assert_eq!(state(&graph), state(&expected));
+    assert_eq!(graph.get_timeline_range(), Some(0..=4));
+}
+
+#[test]
+fn deadlock_stops_the_run() {
+    let mut graph = load(
+        "\
+Task;0;0;0;A;a;0;1;0;1
+Task;0;0;1;B;b;0;1;0;0
+Mutex;0;0;0;0;0
+Mutex;0;0;1;0;1
+",
+    );
+    assert_eq!(graph.run(10), 1);
+    let deadlock = graph.get_deadlock().unwrap();
+    assert_eq!(deadlock.tick, 0);
+    assert!(deadlock.blocked_activities == vec![ActivityNodeId(0), ActivityNodeId(1)]);
+    assert!(deadlock.blocking_mutexes == vec![MutexNodeId(0), MutexNodeId(1)]);
+}
+
+#[test]
+fn running_activity_prevents_a_deadlock() {
+    let mut graph = load(PING_PONG);
+    assert_eq!(graph.run(20), 20);
+    assert!(graph.get_deadlock().is_none());
+}
+
+#[test]
+fn exploration_finds_bounds_and_deadlocks() {
+    let analysis = load(PING_PONG).explore_state_space(1000);
+    assert!(analysis.complete);
+    assert!(analysis.deadlock_states.is_empty());
+    assert_eq!(analysis.can_exceed(MutexNodeId(0), 1), Some(false));
+    assert_eq!(analysis.can_exceed(MutexNodeId(0), 0), Some(true));
+
+    // either activity may take the only token, only one order ends in a deadlock
+    let analysis = load(
+        "\
+Task;0;0;0;A;a;0;1;0
+Task;0;0;1;B;b;0;1;0;0
+Mutex;0;0;0;1;0;1
+",
+    )
+    .explore_state_space(1000);
+    assert!(analysis.complete);
+    assert_eq!(analysis.deadlock_states.len(), 1);
+
+    // a producer without inputs fills its mutex forever
+    let analysis = load(
+        "\
+Task;0;0;0;P;P;0;1;0;0
+Mutex;0;0;0;0
+",
+    )
+    .explore_state_space(50);
+    assert!(!analysis.complete);
+    assert_eq!(analysis.state_count, 50);
+    assert_eq!(analysis.can_exceed(MutexNodeId(0), 10), Some(true));
+    assert_eq!(analysis.can_exceed(MutexNodeId(0), 1000), None);
+}
+
+// the ticks in which the given activity was running
+fn running_ticks(graph: &Graph, id: usize) -> Vec<u64> {
+    graph
+        .schedule
+        .records()
+        .filter(|record| {
+            record
+                .statuses
+                .contains(&(ActivityNodeId(id), super::gantt::ActivityStatus::Running))
+        })
+        .map(|record| record.tick)
+        .collect()
+}
+
+#[test]
+fn schedule_records_running_ticks() {
+    let mut graph = load(PING_PONG);
+    graph.run(6);
+    assert_eq!(graph.schedule.records().count(), 6);
+    assert_eq!(running_ticks(&graph, 0), vec![0, 1, 3, 4]);
+    assert_eq!(running_ticks(&graph, 1), vec![2, 5]);
+
+    // rewinding and stepping again replaces the recorded ticks
+    graph.rewind_to(2);
+    graph.run(2);
+    assert_eq!(graph.schedule.records().count(), 4);
+    assert_eq!(running_ticks(&graph, 1), vec![2]);
+}
+
+#[test]
+fn trace_exports_events_as_csv_and_json() {
+    let mut graph = load(PING_PONG);
+    graph.run(2);
+    assert_eq!(
+        graph.get_trace().to_csv(),
+        "\
+Tick;Event;Node ID;Value
+0;Activity Released;0;1
+0;Activity Started;0;2
+0;Mutex Decremented;1;0
+1;Activity Finished;0;0
+1;Mutex Incremented;0;1
+"
+    );
+
+    let json = serde_json::from_str::<serde_json::Value>(&graph.get_trace().to_json()).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!([
+            { "tick": 0, "event": "activity_released", "node_id": 0, "value": 1 },
+            { "tick": 0, "event": "activity_started", "node_id": 0, "value": 2 },
+            { "tick": 0, "event": "mutex_decremented", "node_id": 1, "value": 0 },
+            { "tick": 1, "event": "activity_finished", "node_id": 0, "value": 0 },
+            { "tick": 1, "event": "mutex_incremented", "node_id": 0, "value": 1 },
+        ])
+    );
+}
+
+#[test]
+fn statistics_count_running_and_waiting_ticks() {
+    let mut graph = load(PING_PONG);
+    graph.run(6);
+    let statistics = &graph.statistics;
+    assert_eq!(statistics.ticks, 6);
+
+    let a = &statistics.activities[&ActivityNodeId(0)];
+    assert_eq!((a.completed_runs, a.running_ticks), (2, 4));
+    assert_eq!((a.waiting_ticks, a.waits, a.longest_wait), (2, 2, 1));
+    let b = &statistics.activities[&ActivityNodeId(1)];
+    assert_eq!((b.completed_runs, b.running_ticks), (2, 2));
+    assert_eq!((b.waiting_ticks, b.waits, b.longest_wait), (4, 2, 2));
+    assert_eq!(b.average_wait(), 2.);
+
+    let m = &statistics.mutexes[&MutexNodeId(0)];
+    assert_eq!((m.min, m.max), (0, 1));
+}
+
+// three activities without inputs that are always ready
+const ALWAYS_READY: &str = "\
+Task;0;0;0;A;a;0;2;0
+Task;0;0;1;B;b;0;3;0
+Task;0;0;2;C;c;0;1;0
+";
+
+// the highest number of activities that were running in the same tick
+fn max_running(graph: &Graph) -> usize {
+    graph
+        .schedule
+        .records()
+        .map(|record| {
+            record
+                .statuses
+                .iter()
+                .filter(|(_, status)| *status == super::gantt::ActivityStatus::Running)
+                .count()
+        })
+        .max()
+        .unwrap_or_default()
+}
+
+#[test]
+fn core_limit_bounds_running_activities() {
+    let mut graph = load(ALWAYS_READY);
+    graph.run(12);
+    assert_eq!(max_running(&graph), 3);
+
+    for cores in 1..=2 {
+        let mut graph = load(&format!("{ALWAYS_READY}Setting;Cores;{cores}\n"));
+        assert_eq!(graph.core_count, Some(cores));
+        graph.run(12);
+        assert_eq!(max_running(&graph), cores as usize);
+        // every activity gets a turn eventually
+        assert!((0..3).all(|id| !running_ticks(&graph, id).is_empty()));
+    }
+
+    assert!(Graph::from_csv(&format!("{ALWAYS_READY}Setting;Cores;0\n")).is_err());
+}
+
+// L is in the middle of its run when H becomes ready on the only core
+const PREEMPTION: &str = "\
+Task;0;0;0;L;low;1;4;3
+Task;0;0;1;H;high;2;1;0
+Mutex;0;0;0;0;0
+Mutex;0;0;1;1;1
+Setting;Cores;1
+";
+
+#[test]
+fn higher_priority_preempts_and_the_activity_resumes_later() {
+    let mut graph = load(&format!("{PREEMPTION}Setting;Preemptive;true\n"));
+    graph.step();
+    assert_eq!(running_ticks(&graph, 1), vec![0]);
+    assert!(graph.activity_nodes[&ActivityNodeId(0)].preempted);
+    assert_eq!(remaining_duration(&graph, 0), 3);
+
+    graph.run(4);
+    assert_eq!(running_ticks(&graph, 0), vec![1, 2, 3]);
+    assert_eq!(remaining_duration(&graph, 0), 0);
+    assert!(graph.get_trace().events().any(|event| {
+        event.event == super::trace::EventKind::ActivityResumed && event.tick == 1
+    }));
+}
+
+#[test]
+fn without_preemption_running_activities_keep_their_core() {
+    let mut graph = load(PREEMPTION);
+    graph.run(5);
+    assert_eq!(running_ticks(&graph, 0), vec![0, 1, 2]);
+    assert_eq!(running_ticks(&graph, 1), vec![3]);
+}
+
+#[test]
+fn earliest_deadline_first_ignores_priorities() {
+    let mut graph = load(
+        "\
+Task;0;0;0;A;a;5;1;0
+Task;0;0;1;B;b;1;1;0
+TaskSetting;0;Deadline;5
+TaskSetting;1;Deadline;2
+Setting;Cores;1
+Setting;Scheduling Policy;Earliest Deadline First
+",
+    );
+    graph.run(6);
+    // B always has the earlier deadline, until A's deadline comes closer
+    assert_eq!(&running_ticks(&graph, 1)[..3], &[0, 1, 2]);
+    assert!(running_ticks(&graph, 0)
+        .first()
+        .is_some_and(|tick| (3..=4).contains(tick)));
+}
+
+#[test]
+fn rate_monotonic_prefers_shorter_periods() {
+    let mut graph = load(
+        "\
+Task;0;0;0;A;a;5;1;0
+Task;0;0;1;B;b;1;1;0
+TaskSetting;0;Period;5
+TaskSetting;1;Period;2
+Setting;Cores;1
+Setting;Scheduling Policy;Rate Monotonic
+",
+    );
+    graph.run(4);
+    assert_eq!(running_ticks(&graph, 1), vec![0, 2]);
+    assert_eq!(running_ticks(&graph, 0), vec![1]);
+}
+
+#[test]
+fn round_robin_rotates_after_the_quantum() {
+    let mut graph = load(
+        "\
+Task;0;0;0;A;a;5;4;0
+Task;0;0;1;B;b;1;4;0
+Task;0;0;2;C;c;3;4;0
+Setting;Cores;1
+Setting;Scheduling Policy;Round Robin
+Setting;Quantum;2
+",
+    );
+    graph.run(12);
+    assert_eq!(running_ticks(&graph, 0), vec![0, 1, 6, 7]);
+    assert_eq!(running_ticks(&graph, 1), vec![2, 3, 8, 9]);
+    assert_eq!(running_ticks(&graph, 2), vec![4, 5, 10, 11]);
+}
+
+// the ticks in which the given activity was started
+fn start_ticks(graph: &Graph, id: usize) -> Vec<u64> {
+    graph
+        .get_trace()
+        .events()
+        .filter(|event| {
+            event.event == super::trace::EventKind::ActivityStarted && event.node_id == id
+        })
+        .map(|event| event.tick)
+        .collect()
+}
+
+#[test]
+fn periodic_activities_are_released_once_per_period() {
+    let mut graph = load(
+        "\
+Task;0;0;0;A;a;0;1;0
+TaskSetting;0;Period;4
+TaskSetting;0;Phase;1
+",
+    );
+    graph.run(12);
+    assert_eq!(start_ticks(&graph, 0), vec![1, 5, 9]);
+}
+
+// C consumes every unit the mutex gains, every 3 ticks starting at tick 1
+const PERIODIC_MUTEX: &str = "\
+Task;0;0;0;C;c;0;1;0
+Mutex;0;0;0;0;0
+MutexSetting;0;Period;3
+MutexSetting;0;Phase;1
+";
+
+#[test]
+fn periodic_mutexes_gain_a_unit_once_per_period() {
+    let mut graph = load(PERIODIC_MUTEX);
+    assert_eq!(graph.run(10), 10);
+    assert_eq!(start_ticks(&graph, 0), vec![1, 4, 7]);
+    // waiting for the next unit is not a deadlock
+    assert!(graph.get_deadlock().is_none());
+}
+
+#[test]
+fn periodic_mutexes_do_not_exceed_their_capacity() {
+    let mut graph = load(
+        "\
+Mutex;0;0;0;0
+MutexSetting;0;Period;1
+MutexSetting;0;Capacity;2
+",
+    );
+    graph.run(5);
+    assert_eq!(graph.get_mutex_node(MutexNodeId(0)).unwrap().value, 2);
+}
+
+#[test]
+fn analysis_repeats_after_the_period_of_a_mutex() {
+    let analysis = load(PERIODIC_MUTEX).explore_state_space(1000);
+    assert!(analysis.complete);
+    assert!(analysis.deadlock_states.is_empty());
+    // the tick before the phase and the three ticks of the period
+    assert_eq!(analysis.state_count, 4);
+}
+
+#[test]
+fn late_releases_are_kept_until_the_activity_can_start() {
+    // every run takes longer than the period, so releases pile up
+    let mut graph = load(
+        "\
+Task;0;0;0;A;a;0;3;0
+TaskSetting;0;Period;2
+",
+    );
+    graph.run(9);
+    assert_eq!(start_ticks(&graph, 0), vec![0, 3, 6]);
+    assert_eq!(graph.activity_nodes[&ActivityNodeId(0)].pending_releases, 2);
+}
+
+// B misses its deadline in every period, A takes the only core first
+const DEADLINE_MISSES: &str = "\
+Task;0;0;0;A;a;1;3;0
+Task;0;0;1;B;b;0;2;0
+TaskSetting;0;Period;4
+TaskSetting;0;Deadline;4
+TaskSetting;1;Period;4
+TaskSetting;1;Deadline;4
+Setting;Cores;1
+";
+
+#[test]
+fn deadline_misses_are_detected() {
+    let mut graph = load(DEADLINE_MISSES);
+    graph.run(12);
+    let deadline_miss = graph.get_deadline_miss().unwrap();
+    assert_eq!(deadline_miss.tick, 4);
+    assert!(deadline_miss.activity == ActivityNodeId(1));
+    assert!(graph
+        .get_trace()
+        .events()
+        .filter(|event| event.event == super::trace::EventKind::DeadlineMissed)
+        .all(|event| event.node_id == 1));
+    assert!(graph.statistics.activities[&ActivityNodeId(0)].deadline_misses == 0);
+    assert!(graph.statistics.activities[&ActivityNodeId(1)].deadline_misses > 0);
+
+    graph.dismiss_deadline_miss();
+    assert!(graph.get_deadline_miss().is_none());
+}
+
+#[test]
+fn run_pauses_on_deadline_misses_if_enabled() {
+    let mut graph = load(DEADLINE_MISSES);
+    graph.pause_on_deadline_miss = true;
+    assert_eq!(graph.run(30), 5);
+    assert_eq!(graph.get_deadline_miss().unwrap().tick, 4);
+}
+
+#[test]
+fn same_seed_reproduces_sampled_durations() {
+    let csv = "\
+Task;0;0;0;A;a;0;1;0
+TaskSetting;0;Duration Distribution;Uniform 1 9
+";
+    let run = |seed: u32| {
+        let mut graph = load(csv);
+        graph.seed = seed;
+        graph.run(100);
+        graph
+            .get_trace()
+            .events()
+            .filter(|event| event.event == super::trace::EventKind::ActivityStarted)
+            .map(|event| event.value)
+            .collect::<Vec<_>>()
+    };
+    let durations = run(3);
+    assert_eq!(durations, run(3));
+    assert!(durations.iter().all(|duration| (1..=9).contains(duration)));
+    assert!(durations.iter().any(|duration| *duration != durations[0]));
+}
+
+#[test]
+fn distributions_only_sample_possible_durations() {
+    use super::distribution::DurationDistribution;
+    use rand::SeedableRng as _;
+
+    let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+    for text in ["Uniform 2 5", "Discrete 2:1 5:3 7:0", "Best/Worst Case 3 8"] {
+        let distribution = DurationDistribution::from_text(text).unwrap();
+        assert_eq!(distribution.to_text().as_deref(), Some(text));
+        let possible_durations = distribution.possible_durations(1);
+        assert!((0..100)
+            .map(|_| distribution.sample(1, &mut rng))
+            .all(|duration| possible_durations.contains(&duration)));
+    }
+    assert_eq!(
+        DurationDistribution::from_text("Discrete 2:1 5:3 7:0")
+            .unwrap()
+            .possible_durations(1),
+        vec![2, 5]
+    );
+    assert!(DurationDistribution::from_text("Uniform 2").is_none());
+    assert!(DurationDistribution::from_text("Normal 2 5").is_none());
+}
+
+#[test]
+fn weights_consume_and_produce_several_units() {
+    // P produces 2 units per run, C needs 3 of them to start
+    let mut graph = load(
+        "\
+Task;0;0;0;P;P;0;1;0;0:2
+Task;0;0;1;C;C;0;1;0
+Mutex;0;0;0;0;1:3
+",
+    );
+    graph.run(2);
+    assert_eq!(mutex_value(&graph, 0), 4);
+    assert!(start_ticks(&graph, 1).is_empty());
+    graph.run(1);
+    assert_eq!(start_ticks(&graph, 1), vec![2]);
+    assert_eq!(mutex_value(&graph, 0), 3);
+
+    let connection = &graph.connections[&ActivityNodeId(0)][&MutexNodeId(0)];
+    assert_eq!(connection.get_output_weight(), 2);
+    let connection = &graph.connections[&ActivityNodeId(1)][&MutexNodeId(0)];
+    assert_eq!(connection.get_input_weight(), 3);
+    assert!(Graph::from_csv("Task;0;0;0;P;P;0;1;0;0:x\nMutex;0;0;0;0\n").is_err());
+}
+
+#[test]
+fn large_weights_do_not_overflow_the_mutex() {
+    let mut graph = load("Task;0;0;0;P;P;0;1;0;0:5000\nMutex;0;0;0;4294967000\n");
+    let connection = &graph.connections[&ActivityNodeId(0)][&MutexNodeId(0)];
+    assert_eq!(connection.get_output_weight(), 999);
+    graph.run(1);
+    assert_eq!(mutex_value(&graph, 0), u32::MAX);
+}
+
+#[test]
+fn capacity_blocks_producers_of_full_mutexes() {
+    let mut graph = load(
+        "\
+Task;0;0;0;P;P;0;1;0;0
+Task;0;0;1;C;C;0;3;0
+Mutex;0;0;0;0;1
+MutexSetting;0;Capacity;2
+",
+    );
+    assert_eq!(
+        graph.get_mutex_node(MutexNodeId(0)).unwrap().capacity,
+        Some(2)
+    );
+    graph.run(12);
+    assert!(graph
+        .get_trace()
+        .events()
+        .filter(|event| event.event == super::trace::EventKind::MutexIncremented)
+        .all(|event| event.value <= 2));
+    // the producer waits for the consumer instead of exceeding the capacity
+    assert!(start_ticks(&graph, 0).len() < 12);
+    assert!(graph.get_deadlock().is_none());
+}
+
+#[test]
+fn inhibitor_blocks_while_the_mutex_holds_enough_units() {
+    // I may only start while the mutex is empty, W takes the units of R
+    let mut graph = load(
+        "\
+Task;0;0;0;R;R;0;1;0;0
+Task;0;0;1;W;W;0;2;0
+Task;0;0;2;I;I;0;1;0
+Mutex;0;0;0;1;1;!2
+TaskSetting;0;Period;4
+TaskSetting;0;Phase;1
+",
+    );
+    graph.run(1);
+    assert!(start_ticks(&graph, 2).is_empty());
+    assert_eq!(start_ticks(&graph, 1), vec![0]);
+    graph.run(1);
+    assert_eq!(start_ticks(&graph, 2), vec![1]);
+    // the inhibitor does not take anything from the mutex
+    graph.run(1);
+    assert_eq!(mutex_value(&graph, 0), 0);
+    assert_eq!(start_ticks(&graph, 2), vec![1, 2]);
+
+    let connection = &graph.connections[&ActivityNodeId(2)][&MutexNodeId(0)];
+    assert!(connection.get_input_kind() == super::connection::InputKind::Inhibitor);
+}
+
+#[test]
+fn read_inputs_check_without_consuming() {
+    let mut graph = load(
+        "\
+Task;0;0;0;A;A;0;2;0
+Task;0;0;1;B;B;0;3;0
+Mutex;0;0;0;1;?0;?1
+",
+    );
+    graph.run(6);
+    assert_eq!(start_ticks(&graph, 0), vec![0, 2, 4]);
+    assert_eq!(start_ticks(&graph, 1), vec![0, 3]);
+    assert_eq!(mutex_value(&graph, 0), 1);
+
+    // without units, nothing reads
+    let mut graph = load(
+        "\
+Task;0;0;0;A;A;0;2;0
+Mutex;0;0;0;0;?0
+",
+    );
+    assert_eq!(graph.run(5), 1);
+    assert!(graph.get_deadlock().is_some());
+}
+
+#[test]
+fn reset_outputs_clear_the_mutex() {
+    let mut graph = load(
+        "\
+Task;0;0;0;P;P;0;1;0;0
+Task;0;0;1;F;F;0;1;0;~0
+TaskSetting;1;Period;5
+TaskSetting;1;Phase;4
+Mutex;0;0;0;0
+",
+    );
+    graph.run(4);
+    assert_eq!(mutex_value(&graph, 0), 4);
+    graph.run(1);
+    // P produced one more unit in the same tick, the reset is applied by F
+    assert_eq!(mutex_value(&graph, 0), 0);
+    assert!(graph
+        .get_trace()
+        .events()
+        .any(|event| event.event == super::trace::EventKind::MutexReset && event.tick == 4));
+}
+
+#[test]
+fn barrier_releases_waiting_activities_together() {
+    // A and B arrive, their next activities wait for both
+    let mut graph = load(
+        "\
+Task;0;0;0;A;work;0;1;0
+Task;0;0;1;B;work;0;3;0
+Task;0;0;2;A;next;0;1;0;0
+Task;0;0;3;B;next;0;1;0;1
+Mutex;0;0;0;1;0
+Mutex;0;0;1;1;1
+Barrier;0;0;0;2;;+0;+1;2;3
+",
+    );
+    graph.run(1);
+    assert_eq!(
+        graph
+            .get_barrier_node(BarrierNodeId(0))
+            .unwrap()
+            .arrived
+            .len(),
+        1
+    );
+    graph.run(2);
+    assert!(start_ticks(&graph, 2).is_empty());
+    // the last arrival releases the barrier right away
+    assert!(graph
+        .get_barrier_node(BarrierNodeId(0))
+        .unwrap()
+        .arrived
+        .is_empty());
+    graph.run(1);
+    assert_eq!(start_ticks(&graph, 2), vec![3]);
+    assert_eq!(start_ticks(&graph, 3), vec![3]);
+}
+
+#[test]
+fn barrier_counts_every_arriving_activity_once() {
+    // A finishes in every tick, but B never arrives
+    let mut graph = load(
+        "\
+Task;0;0;0;A;work;0;1;0
+Task;0;0;1;B;work;0;1;0
+Task;0;0;2;W;wait;0;1;0
+Mutex;0;0;0;0;1
+Barrier;0;0;0;2;;+0;+1;2
+",
+    );
+    graph.run(5);
+    assert_eq!(start_ticks(&graph, 0).len(), 5);
+    assert!(start_ticks(&graph, 2).is_empty());
+    let barrier_node = graph.get_barrier_node(BarrierNodeId(0)).unwrap();
+    assert!(barrier_node.arrived.iter().eq([&ActivityNodeId(0)]));
+}
+
+#[test]
+fn barrier_count_follows_the_arriving_activities() {
+    let mut graph = load("Barrier;0;0;0;1;\n");
+    graph.toggle_barrier_connection(ActivityNodeId(0), BarrierNodeId(0), true);
+    graph.toggle_barrier_connection(ActivityNodeId(1), BarrierNodeId(0), true);
+    assert_eq!(graph.get_barrier_node(BarrierNodeId(0)).unwrap().count, 2);
+    graph.toggle_barrier_connection(ActivityNodeId(1), BarrierNodeId(0), true);
+    assert_eq!(graph.get_barrier_node(BarrierNodeId(0)).unwrap().count, 1);
+    // a count that can never be reached is rejected
+    assert!(Graph::from_csv("Barrier;0;0;0;2;;+0\n").is_err());
+    assert!(Graph::from_csv("Barrier;0;0;0;2;1;+0;+2\n").is_err());
+}
+
+#[test]
+fn barrier_that_is_never_released_deadlocks() {
+    let mut graph = load(
+        "\
+Task;0;0;0;A;work;0;1;0
+Task;0;0;1;A;next;0;1;0
+Task;0;0;2;B;work;0;1;0
+Mutex;0;0;0;1;0
+Mutex;0;0;1;0;2
+Barrier;0;0;0;2;;+0;+2;1
+",
+    );
+    graph.run(10);
+    let deadlock = graph.get_deadlock().unwrap();
+    assert!(deadlock.blocking_barriers == vec![BarrierNodeId(0)]);
+}
+
+#[test]
+fn lock_is_shared_by_readers_and_exclusive_for_writers() {
+    let mut graph = load(
+        "\
+Task;0;0;0;R;read a;2;2;0
+Task;0;0;1;R;read b;2;3;0
+Task;0;0;2;W;write;1;2;0
+Mutex;0;0;0;1;0
+Mutex;0;0;1;1;1
+Lock;0;0;0;?0;?1;2
+",
+    );
+    graph.run(1);
+    // both readers hold the lock, the writer has to wait
+    assert!(
+        graph.lock_holders(LockNodeId(0))
+            == vec![
+                (ActivityNodeId(0), LockAccess::Shared),
+                (ActivityNodeId(1), LockAccess::Shared)
+            ]
+    );
+    assert!(start_ticks(&graph, 2).is_empty());
+    // the writer starts once the last reader released the lock
+    graph.run(3);
+    assert_eq!(start_ticks(&graph, 2), vec![3]);
+    assert!(graph.lock_holders(LockNodeId(0)) == vec![(ActivityNodeId(2), LockAccess::Exclusive)]);
+}
+
+#[test]
+fn writers_never_hold_the_lock_together() {
+    let mut graph = load(
+        "\
+Task;0;0;0;W;write a;0;2;0
+Task;0;0;1;W;write b;0;1;0
+Lock;0;0;0;0;1
+",
+    );
+    graph.run(10);
+    assert_eq!(max_running(&graph), 1);
+    assert!(!running_ticks(&graph, 0).is_empty());
+    assert!(!running_ticks(&graph, 1).is_empty());
+}
+
+// Low holds the mutex that High needs, Med is released in between
+const PRIORITY_INVERSION: &str = "\
+Task;0;0;0;Low;data;1;4;0;0
+Task;0;0;1;Med;comm;2;5;0
+Task;0;0;2;High;bus;3;1;0;0
+Mutex;0;0;0;1;0;2
+TaskSetting;0;Period;20
+TaskSetting;1;Period;20
+TaskSetting;1;Phase;1
+TaskSetting;2;Period;20
+TaskSetting;2;Phase;2
+Setting;Cores;1
+Setting;Preemptive;true
+";
+
+fn priority_inversion(priority_protocol: PriorityProtocol) -> Graph {
+    let mut graph = load(PRIORITY_INVERSION);
+    graph.priority_protocol = priority_protocol;
+    graph.run(12);
+    graph
+}
+
+#[test]
+fn without_protocol_medium_priority_delays_high_priority() {
+    let graph = priority_inversion(PriorityProtocol::None);
+    assert_eq!(running_ticks(&graph, 0), vec![0, 6, 7, 8]);
+    assert_eq!(running_ticks(&graph, 1), vec![1, 2, 3, 4, 5]);
+    assert_eq!(running_ticks(&graph, 2), vec![9]);
+}
+
+#[test]
+fn inheritance_raises_the_holder_to_the_waiting_priority() {
+    let graph = priority_inversion(PriorityProtocol::Inheritance);
+    assert_eq!(running_ticks(&graph, 0), vec![0, 2, 3, 4]);
+    assert_eq!(running_ticks(&graph, 2), vec![5]);
+    assert_eq!(running_ticks(&graph, 1), vec![1, 6, 7, 8, 9]);
+}
+
+#[test]
+fn ceiling_raises_the_holder_as_soon_as_it_holds_the_mutex() {
+    let mut graph = load(PRIORITY_INVERSION);
+    graph.priority_protocol = PriorityProtocol::Ceiling;
+    graph.step();
+    assert_eq!(graph.effective_priorities()[&ActivityNodeId(0)], 3);
+
+    let graph = priority_inversion(PriorityProtocol::Ceiling);
+    assert_eq!(running_ticks(&graph, 0), vec![0, 1, 2, 3]);
+    assert_eq!(running_ticks(&graph, 2), vec![4]);
+    assert_eq!(running_ticks(&graph, 1), vec![5, 6, 7, 8, 9]);
+}
+
+#[test]
+fn task_holds_a_mutex_from_taking_a_unit_until_giving_it_back() {
+    // Low takes the unit in its first activity and gives it back from its second one
+    let mut graph = load(
+        "\
+Task;0;0;0;Low;acquire;1;1;0;1
+Task;0;0;1;Low;release;1;2;0;0
+Task;0;0;2;High;use;3;1;0;0
+Mutex;0;0;0;1;0;2
+Mutex;0;0;1;0;1
+TaskSetting;2;Period;20
+TaskSetting;2;Phase;5
+Setting;Priority Protocol;Ceiling
+",
+    );
+    graph.step();
+    assert!(graph.mutex_holders(MutexNodeId(0)) == vec![ActivityNodeId(0), ActivityNodeId(1)]);
+    assert_eq!(graph.effective_priorities()[&ActivityNodeId(1)], 3);
+    graph.run(2);
+    assert!(graph.mutex_holders(MutexNodeId(0)).is_empty());
+    assert_eq!(graph.effective_priorities()[&ActivityNodeId(1)], 1);
+}
+
+#[test]
+fn queue_passes_labels_in_order() {
+    let mut graph = load(
+        "\
+Task;0;0;0;P;red;0;2;0
+Task;0;0;1;P;blue;0;3;0
+Task;0;0;2;C;consume;0;1;0
+Queue;0;0;0;green;+0=red;+1=blue;2
+TaskSetting;0;Period;4
+TaskSetting;1;Period;6
+",
+    );
+    let mut received = Vec::new();
+    for _ in 0..12 {
+        graph.step();
+        if let Some(label) = graph
+            .get_queue_node(QueueNodeId(0))
+            .unwrap()
+            .received
+            .get(&ActivityNodeId(2))
+        {
+            if start_ticks(&graph, 2).last() == Some(&(graph.get_current_tick() - 1)) {
+                received.push(label.clone());
+            }
+        }
+    }
+    assert_eq!(received, vec!["green", "red", "blue", "red", "blue", "red"]);
+    assert!(graph
+        .get_queue_node(QueueNodeId(0))
+        .unwrap()
+        .tokens
+        .is_empty());
+}
+
+#[test]
+fn empty_queue_blocks_consumers() {
+    let mut graph = load(
+        "\
+Task;0;0;0;C;consume;0;1;0
+Queue;0;0;0;;0
+",
+    );
+    assert_eq!(graph.run(5), 1);
+    assert!(graph.get_deadlock().unwrap().blocking_queues == vec![QueueNodeId(0)]);
+}
+
+// nodes and connections are stored in hash maps, so their order in the file is not fixed
+fn normalized(csv: &str) -> Vec<Vec<String>> {
+    let mut lines = csv
+        .lines()
+        .filter(|line| !line.starts_with('#') && !line.starts_with("Type;"))
+        .map(|line| {
+            let mut fields = line
+                .trim_end_matches(';')
+                .split(';')
+                .map(str::to_string)
+                .collect::<Vec<_>>();
+            let first_connection = match fields[0].as_str() {
+                "Task" => 9,
+                "Mutex" | "Queue" => 5,
+                "Barrier" => 6,
+                "Lock" => 4,
+                _ => fields.len(),
+            };
+            let first_connection = first_connection.min(fields.len());
+            fields[first_connection..].sort();
+            fields
+        })
+        .collect::<Vec<_>>();
+    lines.sort();
+    lines
+}
+
+// every kind of entry and setting the file format knows
+const ALL_ENTRIES: &str = "\
+Task;10;20;0;A;a;1;2;0;0:2;~1
+Task;30;40;1;B;b;2;3;1;1
+Task;50;60;2;C;c;3;1;0
+Mutex;0;0;0;1;1:3;?2
+Mutex;0;0;1;2;!2:2
+Barrier;0;0;0;2;1;+0;+1;2
+Lock;0;0;0;?0;1
+Queue;0;0;0;red blue;+0=red;2
+TaskSetting;0;Period;5
+TaskSetting;0;Phase;1
+TaskSetting;0;Deadline;4
+TaskSetting;1;Duration Distribution;Discrete 2:1 5:3
+TaskSetting;2;Duration Distribution;Best/Worst Case 1 4
+MutexSetting;1;Capacity;3
+MutexSetting;0;Period;4
+MutexSetting;0;Phase;2
+Setting;Seed;42
+Setting;Tie Breaking;Round Robin
+Setting;Cores;2
+Setting;Scheduling Policy;Round Robin
+Setting;Priority Protocol;Ceiling
+Setting;Quantum;3
+Setting;Preemptive;true
+Setting;Pause On Deadline Miss;true
+";
+
+#[test]
+fn csv_round_trip_keeps_every_entry() {
+    let graph = load(ALL_ENTRIES);
+    let csv = graph.to_csv();
+    let reloaded = Graph::from_csv(&csv).unwrap();
+    assert_eq!(normalized(&reloaded.to_csv()), normalized(&csv));
+    assert_eq!(normalized(&csv), normalized(ALL_ENTRIES));
+
+    assert_eq!(reloaded.seed, 42);
+    assert_eq!(reloaded.core_count, Some(2));
+    assert_eq!(reloaded.quantum, 3);
+    assert!(reloaded.preemptive);
+    assert!(reloaded.pause_on_deadline_miss);
+    assert!(reloaded.priority_protocol == PriorityProtocol::Ceiling);
+
+    let activity_node = reloaded.get_activity_node(ActivityNodeId(0)).unwrap();
+    assert_eq!(
+        (
+            activity_node.period,
+            activity_node.phase,
+            activity_node.deadline
+        ),
+        (5, 1, 4)
+    );
+    assert!(
+        reloaded
+            .get_activity_node(ActivityNodeId(1))
+            .unwrap()
+            .duration_distribution
+            == DurationDistribution::Discrete(vec![(2, 1), (5, 3)])
+    );
+    assert_eq!(
+        reloaded.get_mutex_node(MutexNodeId(1)).unwrap().capacity,
+        Some(3)
+    );
+
+    let connection = &reloaded.connections[&ActivityNodeId(0)][&MutexNodeId(0)];
+    assert_eq!(connection.get_output_weight(), 2);
+    let connection = &reloaded.connections[&ActivityNodeId(0)][&MutexNodeId(1)];
+    assert!(connection.get_output_kind() == super::connection::OutputKind::Reset);
+    let connection = &reloaded.connections[&ActivityNodeId(1)][&MutexNodeId(0)];
+    assert_eq!(connection.get_input_weight(), 3);
+    let connection = &reloaded.connections[&ActivityNodeId(2)][&MutexNodeId(0)];
+    assert!(connection.get_input_kind() == super::connection::InputKind::Read);
+    let connection = &reloaded.connections[&ActivityNodeId(2)][&MutexNodeId(1)];
+    assert!(connection.get_input_kind() == super::connection::InputKind::Inhibitor);
+    assert_eq!(connection.get_input_weight(), 2);
+
+    let barrier_node = reloaded.get_barrier_node(BarrierNodeId(0)).unwrap();
+    assert_eq!((barrier_node.count, barrier_node.arrived.len()), (2, 1));
+    assert!(barrier_node.arriving.contains(&ActivityNodeId(0)));
+    assert!(barrier_node.waiting.contains(&ActivityNodeId(2)));
+
+    let lock_node = reloaded.get_lock_node(LockNodeId(0)).unwrap();
+    assert_eq!(lock_node.access[&ActivityNodeId(0)], LockAccess::Shared);
+    assert_eq!(lock_node.access[&ActivityNodeId(1)], LockAccess::Exclusive);
+
+    let queue_node = reloaded.get_queue_node(QueueNodeId(0)).unwrap();
+    assert_eq!(queue_node.tokens, ["red", "blue"]);
+    assert_eq!(queue_node.producers[&ActivityNodeId(0)], "red");
+    assert!(queue_node.consumers.contains(&ActivityNodeId(2)));
+}
+
+#[test]
+fn duration_distributions_are_bounded() {
+    use rand::SeedableRng as _;
+
+    assert!(
+        DurationDistribution::from_text("Uniform 1 4000000000")
+            == Some(DurationDistribution::Uniform { min: 1, max: 999 })
+    );
+    let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+    let uniform = DurationDistribution::Uniform { min: 1, max: 999 };
+    assert!((0..100)
+        .map(|_| uniform.sample(2, &mut rng))
+        .all(|duration| (1..=999).contains(&duration)));
+    // a fixed duration is used as it is, even if it is 0
+    assert_eq!(DurationDistribution::Fixed.sample(0, &mut rng), 0);
+    assert_eq!(DurationDistribution::Fixed.possible_durations(0), vec![0]);
+}
+
+#[test]
+fn run_only_stops_on_new_deadline_misses() {
+    let mut graph = load(DEADLINE_MISSES);
+    graph.pause_on_deadline_miss = true;
+    assert_eq!(graph.run(30), 5);
+    // the miss is still shown, but does not stop the next run
+    assert_eq!(graph.run(30), 30);
+
+    graph.dismiss_deadline_miss();
+    assert!(graph.run(30) < 30);
+    assert_eq!(
+        graph.get_deadline_miss().unwrap().tick,
+        graph.get_current_tick() - 1
+    );
+}
+
+#[test]
+fn empty_queue_labels_fall_back_to_the_default() {
+    let graph = load(
+        "\
+Task;0;0;0;P;P;0;1;0
+Queue;0;0;0;;+0=
+",
+    );
+    assert_eq!(
+        graph.get_queue_node(QueueNodeId(0)).unwrap().producers[&ActivityNodeId(0)],
+        "msg"
+    );
+}
+
+#[test]
+fn periodic_activities_do_not_wait_while_idle() {
+    let mut graph = load(DEADLINE_MISSES);
+    graph.pause_on_deadline_miss = false;
+    graph.run(4);
+    let statuses = graph
+        .schedule
+        .records()
+        .map(|record| {
+            record
+                .statuses
+                .iter()
+                .find(|(id, _)| *id == ActivityNodeId(1))
+                .unwrap()
+                .1
+        })
+        .collect::<Vec<_>>();
+    assert!(statuses[..3]
+        .iter()
+        .all(|status| *status == super::gantt::ActivityStatus::Waiting));
+    assert!(statuses[3] == super::gantt::ActivityStatus::Running);
+
+    let mut graph = load(
+        "\
+Task;0;0;0;A;a;0;1;0
+TaskSetting;0;Period;4
+",
+    );
+    graph.run(12);
+    let statistics = &graph.statistics.activities[&ActivityNodeId(0)];
+    assert_eq!(statistics.completed_runs, 3);
+    assert_eq!(statistics.waiting_ticks, 0);
+    assert_eq!(statistics.longest_wait, 0);
+}
+
+#[test]
+fn undo_keeps_the_simulation_state() {
+    let mut graph = load(PING_PONG);
+    let ctx = egui::Context::default();
+    graph.track_history(&ctx);
+
+    graph.activity_nodes[&ActivityNodeId(1)].pos.x = 100.;
+    graph.history.mark_edited();
+    graph.run(4);
+    let state = (remaining_duration(&graph, 0), mutex_value(&graph, 0));
+    graph.undo();
+    assert_eq!(graph.activity_nodes[&ActivityNodeId(1)].pos.x, 0.);
+    assert_eq!(graph.get_current_tick(), 4);
+    assert_eq!(
+        (remaining_duration(&graph, 0), mutex_value(&graph, 0)),
+        state
+    );
+
+    graph.redo();
+    assert_eq!(graph.activity_nodes[&ActivityNodeId(1)].pos.x, 100.);
+    assert_eq!(
+        (remaining_duration(&graph, 0), mutex_value(&graph, 0)),
+        state
+    );
+}
+
+#[test]
+fn undo_reverts_values_changed_by_hand() {
+    let mut graph = load(PING_PONG);
+    let ctx = egui::Context::default();
+    graph.track_history(&ctx);
+
+    graph
+        .history
+        .record_value_edit(super::history::ValueEdit::MutexValue(MutexNodeId(0), 0));
+    graph.mutex_nodes.get_mut(&MutexNodeId(0)).unwrap().value = 5;
+    graph.history.mark_edited();
+    graph.undo();
+    assert_eq!(mutex_value(&graph, 0), 0);
+    graph.redo();
+    assert_eq!(mutex_value(&graph, 0), 5);
+}
+
+#[test]
+fn exploration_in_steps_matches_a_complete_exploration() {
+    let graph = load(PING_PONG);
+    let analysis = graph.explore_state_space(1000);
+    let mut exploration = graph.start_exploration(1000);
+    while !exploration.step(1) {}
+    let stepped_analysis = exploration.into_analysis();
+    assert!(analysis.complete && stepped_analysis.complete);
+    assert_eq!(analysis.state_count, stepped_analysis.state_count);
+    assert_eq!(analysis.transition_count, stepped_analysis.transition_count);
+
+    let mut exploration = graph.start_exploration(1000);
+    exploration.step(1);
+    assert!(!exploration.into_analysis().complete);
+}
+
+#[test]
+fn run_with_calls_back_after_every_tick_and_stops_like_run() {
+    let mut graph = load(DEADLINE_MISSES);
+    graph.pause_on_deadline_miss = true;
+    let mut ticks = Vec::new();
+    let executed_ticks = graph.run_with(30, |graph| {
+        ticks.push(graph.get_current_tick());
+        Ok::<(), ()>(())
+    });
+    assert_eq!(executed_ticks, Ok(5));
+    assert_eq!(ticks, vec![1, 2, 3, 4, 5]);
+
+    // an error stops the run right away
+    let mut graph = load(PING_PONG);
+    let executed_ticks = graph.run_with(30, |graph| match graph.get_current_tick() {
+        3 => Err("stop"),
+        _ => Ok(()),
+    });
+    assert_eq!(executed_ticks, Err("stop"));
+    assert_eq!(graph.get_current_tick(), 3);
+}
+
+#[test]
+fn rewinding_discards_the_trace_and_schedule_of_later_ticks() {
+    let mut graph = load(PING_PONG);
+    graph.run(6);
+    graph.rewind_to(2);
+    assert!(graph.get_trace().events().all(|event| event.tick < 2));
+    assert_eq!(graph.schedule.records().count(), 2);
+    assert_eq!(graph.get_timeline_range(), Some(0..=2));
+
+    graph.run(4);
+    let mut expected = load(PING_PONG);
+    expected.run(6);
+    assert_eq!(graph.get_trace().to_csv(), expected.get_trace().to_csv());
+    let ticks = graph
+        .schedule
+        .records()
+        .map(|record| record.tick)
+        .collect::<Vec<_>>();
+    assert_eq!(ticks, vec![0, 1, 2, 3, 4, 5]);
+}
+
+// P puts a unit into the mutex every 4 ticks, C is blocked until it is there
+const REFILLED_EVERY_4_TICKS: &str = "\
+Task;0;0;0;P;produce;0;1;0;0
+Task;0;0;1;C;consume;0;1;0
+Mutex;0;0;0;0;1
+TaskSetting;0;Period;4
+";
+
+#[test]
+fn activities_blocked_by_a_mutex_are_waiting() {
+    let mut graph = load(REFILLED_EVERY_4_TICKS);
+    graph.run(12);
+    assert_eq!(running_ticks(&graph, 1), vec![1, 5, 9]);
+
+    let statistics = &graph.statistics.activities[&ActivityNodeId(1)];
+    assert_eq!(statistics.completed_runs, 3);
+    assert_eq!(statistics.waiting_ticks, 9);
+    assert_eq!((statistics.waits, statistics.longest_wait), (4, 3));
+    // the periodic producer is only idle between its releases
+    assert_eq!(
+        graph.statistics.activities[&ActivityNodeId(0)].waiting_ticks,
+        0
+    );
+}
+
+#[test]
+fn gantt_chart_shows_blocked_activities_as_waiting() {
+    use super::gantt::ActivityStatus;
+
+    let mut graph = load(REFILLED_EVERY_4_TICKS);
+    graph.run(4);
+    let statuses = |id| {
+        graph
+            .schedule
+            .records()
+            .map(|record| {
+                record
+                    .statuses
+                    .iter()
+                    .find(|(activity, _)| *activity == ActivityNodeId(id))
+                    .map(|(_, status)| *status)
+                    .unwrap()
+            })
+            .collect::<Vec<_>>()
+    };
+    assert!(
+        statuses(0)
+            == [
+                ActivityStatus::Running,
+                ActivityStatus::Idle,
+                ActivityStatus::Idle,
+                ActivityStatus::Idle
+            ]
+    );
+    assert!(
+        statuses(1)
+            == [
+                ActivityStatus::Waiting,
+                ActivityStatus::Running,
+                ActivityStatus::Waiting,
+                ActivityStatus::Waiting
+            ]
+    );
+}
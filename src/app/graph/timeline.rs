@@ -0,0 +1,91 @@
+use super::{
+    connection::Connection, statistics::Statistics, ActivityNodeId, BarrierNodeId, MutexNodeId,
+    QueueNodeId,
+};
+
+const MAX_TIMELINE_LENGTH: usize = 10_000;
+
+// the part of an activity node that is changed by the simulation
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct ActivityState {
+    pub remaining_duration: u32,
+    pub current_duration: u32,
+    pub last_start_tick: Option<u64>,
+    pub preempted: bool,
+    pub release_tick: u64,
+    pub pending_releases: u32,
+    pub deadline_missed: bool,
+    pub ticks_in_slice: u32,
+}
+
+// the part of a mutex node that is changed by the simulation
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct MutexState {
+    pub value: u32,
+    pub held_by: std::collections::BTreeSet<String>,
+}
+
+// the part of a barrier node that is changed by the simulation
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct BarrierState {
+    pub arrived: std::collections::BTreeSet<ActivityNodeId>,
+    pub passes: std::collections::BTreeMap<ActivityNodeId, u32>,
+}
+
+// the part of a queue node that is changed by the simulation
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct QueueState {
+    pub tokens: std::collections::VecDeque<String>,
+    pub received: std::collections::BTreeMap<ActivityNodeId, String>,
+}
+
+#[derive(Clone)]
+pub struct SimulationSnapshot {
+    pub tick: u64,
+    pub activities: Vec<(ActivityNodeId, ActivityState)>,
+    pub mutexes: Vec<(MutexNodeId, MutexState)>,
+    pub barriers: Vec<(BarrierNodeId, BarrierState)>,
+    pub queues: Vec<(QueueNodeId, QueueState)>,
+    pub connections: Vec<(ActivityNodeId, MutexNodeId, Connection)>,
+    pub statistics: Statistics,
+}
+
+// snapshots of the simulation state at the start of each tick, ordered by tick
+#[derive(Default)]
+pub struct Timeline {
+    snapshots: std::collections::VecDeque<SimulationSnapshot>,
+}
+
+impl Timeline {
+    // replaces the snapshot of the same tick and discards all later ones
+    pub fn record(&mut self, snapshot: SimulationSnapshot) {
+        while self
+            .snapshots
+            .back()
+            .map_or(false, |last| last.tick >= snapshot.tick)
+        {
+            self.snapshots.pop_back();
+        }
+        self.snapshots.push_back(snapshot);
+        if self.snapshots.len() > MAX_TIMELINE_LENGTH {
+            self.snapshots.pop_front();
+        }
+    }
+
+    // forget the snapshots of all ticks after the given one
+    pub fn discard_after(&mut self, tick: u64) {
+        while self.snapshots.back().map_or(false, |last| last.tick > tick) {
+            self.snapshots.pop_back();
+        }
+    }
+
+    pub fn get(&self, tick: u64) -> Option<&SimulationSnapshot> {
+        let first_tick = self.snapshots.front()?.tick;
+        let snapshot = self.snapshots.get(tick.checked_sub(first_tick)? as usize)?;
+        (snapshot.tick == tick).then_some(snapshot)
+    }
+
+    pub fn range(&self) -> Option<std::ops::RangeInclusive<u64>> {
+        Some(self.snapshots.front()?.tick..=self.snapshots.back()?.tick)
+    }
+}
@@ -0,0 +1,104 @@
+const MAX_TRACE_LENGTH: usize = 1_000_000;
+
+#[derive(Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    ActivityReleased,
+    ActivityStarted,
+    ActivityFinished,
+    ActivityPreempted,
+    ActivityResumed,
+    DeadlineMissed,
+    MutexIncremented,
+    MutexDecremented,
+    MutexReset,
+    BarrierArrived,
+    BarrierReleased,
+    LockAcquired,
+    LockReleased,
+    QueuePushed,
+    QueuePopped,
+}
+
+impl EventKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::ActivityReleased => "Activity Released",
+            Self::ActivityStarted => "Activity Started",
+            Self::ActivityFinished => "Activity Finished",
+            Self::ActivityPreempted => "Activity Preempted",
+            Self::ActivityResumed => "Activity Resumed",
+            Self::DeadlineMissed => "Deadline Missed",
+            Self::MutexIncremented => "Mutex Incremented",
+            Self::MutexDecremented => "Mutex Decremented",
+            Self::MutexReset => "Mutex Reset",
+            Self::BarrierArrived => "Barrier Arrived",
+            Self::BarrierReleased => "Barrier Released",
+            Self::LockAcquired => "Lock Acquired",
+            Self::LockReleased => "Lock Released",
+            Self::QueuePushed => "Queue Pushed",
+            Self::QueuePopped => "Queue Popped",
+        }
+    }
+}
+
+// value is the remaining duration for activity events, the new mutex value for mutex events,
+// the arrivals for barrier arrivals, the number of released activities for barrier releases
+// the number of holders for lock events and the number of tokens for queue events
+#[derive(Clone, serde::Serialize)]
+pub struct TraceEvent {
+    pub tick: u64,
+    pub event: EventKind,
+    pub node_id: usize,
+    pub value: u32,
+}
+
+#[derive(Default)]
+pub struct Trace {
+    events: std::collections::VecDeque<TraceEvent>,
+}
+
+impl Trace {
+    pub fn push(&mut self, tick: u64, event: EventKind, node_id: usize, value: u32) {
+        self.events.push_back(TraceEvent {
+            tick,
+            event,
+            node_id,
+            value,
+        });
+        if self.events.len() > MAX_TRACE_LENGTH {
+            self.events.pop_front();
+        }
+    }
+
+    // forget everything that happened in or after the given tick
+    pub fn discard_from(&mut self, tick: u64) {
+        while self.events.back().map_or(false, |last| last.tick >= tick) {
+            self.events.pop_back();
+        }
+    }
+
+    pub fn events(&self) -> impl Iterator<Item = &TraceEvent> {
+        self.events.iter()
+    }
+
+    pub fn to_csv(&self) -> String {
+        let seperator = ";";
+        let mut csv = String::new();
+        csv.push_str("Tick;Event;Node ID;Value\n");
+        for event in &self.events {
+            csv.push_str(&format!(
+                "{}{seperator}{}{seperator}{}{seperator}{}\n",
+                event.tick,
+                event.event.name(),
+                event.node_id,
+                event.value
+            ));
+        }
+        csv
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&self.events).unwrap_or_default()
+    }
+}
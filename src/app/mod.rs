@@ -5,9 +5,22 @@ use egui::{Layout, Pos2};
 use self::graph::Graph;
 use std::future;
 
-mod graph;
+pub mod graph;
 mod graphics;
 
+const MAX_ANALYSIS_STATES: usize = 100_000;
+// keeps the ui responsive while exploring the state space
+const ANALYSIS_STATES_PER_FRAME: usize = 20;
+
+const UNDO_SHORTCUT: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Z);
+const REDO_SHORTCUT: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Y);
+const REDO_SHORTCUT_ALT: egui::KeyboardShortcut = egui::KeyboardShortcut::new(
+    egui::Modifiers::COMMAND.plus(egui::Modifiers::SHIFT),
+    egui::Key::Z,
+);
+
 #[derive(serde::Deserialize, serde::Serialize)]
 #[serde(default)]
 pub struct App {
@@ -17,6 +30,10 @@ pub struct App {
 
     show_about_dialog: bool,
     show_simulation_controls: bool,
+    show_gantt_chart: bool,
+    gantt_chart_group_by_task: bool,
+    show_statistics: bool,
+    show_task_properties: bool,
     pin_menu_bar: bool,
 
     #[serde(skip)]
@@ -28,6 +45,17 @@ pub struct App {
 
     #[serde(skip)]
     seconds_until_hiding_menu_bar: f32,
+
+    #[serde(skip)]
+    analysis: Option<graph::Analysis>,
+    #[serde(skip)]
+    exploration: Option<graph::Exploration>,
+    #[serde(skip)]
+    show_analysis: bool,
+    #[serde(skip)]
+    analysis_mutex: Option<graph::MutexNodeId>,
+    #[serde(skip)]
+    analysis_bound: u32,
 }
 
 #[derive(PartialEq)]
@@ -187,12 +215,21 @@ impl Default for App {
             active_graph: graph,
             show_about_dialog: true,
             show_simulation_controls: true,
+            show_gantt_chart: false,
+            gantt_chart_group_by_task: false,
+            show_statistics: false,
+            show_task_properties: false,
             pin_menu_bar: true,
             scaling_in_percent: 100.,
             text_channel: channel(),
             file_buffer: Default::default(),
             import_state: ImportState::Free,
             seconds_until_hiding_menu_bar: 0.,
+            analysis: None,
+            exploration: None,
+            show_analysis: false,
+            analysis_mutex: None,
+            analysis_bound: 1,
         }
     }
 }
@@ -284,6 +321,19 @@ impl eframe::App for App {
             self.file_buffer.clear();
         }
 
+        self.active_graph.track_history(ctx);
+        // text fields handle their own undo
+        if ctx.memory(|memory| memory.focused().is_none()) {
+            // check the more specific shortcut first, see egui::InputState::consume_shortcut
+            if ctx.input_mut(|i| {
+                i.consume_shortcut(&REDO_SHORTCUT_ALT) || i.consume_shortcut(&REDO_SHORTCUT)
+            }) {
+                self.active_graph.redo();
+            } else if ctx.input_mut(|i| i.consume_shortcut(&UNDO_SHORTCUT)) {
+                self.active_graph.undo();
+            }
+        }
+
         if self.pin_menu_bar || ctx.pointer_interact_pos().map_or(false, |pos| pos.y < 25.) {
             self.seconds_until_hiding_menu_bar = 2.;
         } else if self.seconds_until_hiding_menu_bar > 0. {
@@ -366,6 +416,35 @@ impl eframe::App for App {
                             });
                         }
 
+                        ui.menu_button("⬅ Export Trace", |ui| {
+                            for (label, filter, extension) in [
+                                ("CSV", "Comma Seperated Values", "csv"),
+                                ("JSON", "JavaScript Object Notation", "json"),
+                            ] {
+                                if ui.button(label).clicked() {
+                                    ui.close_menu();
+                                    let task = rfd::AsyncFileDialog::new()
+                                        .add_filter(filter, &[extension])
+                                        .add_filter("All Files", &["*"])
+                                        .set_file_name(format!(
+                                            "{} Trace.{}",
+                                            self.active_graph.name, extension
+                                        ))
+                                        .save_file();
+                                    let contents = match extension {
+                                        "json" => self.active_graph.get_trace().to_json(),
+                                        _ => self.active_graph.get_trace().to_csv(),
+                                    };
+                                    execute(async move {
+                                        let file = task.await;
+                                        if let Some(file) = file {
+                                            _ = file.write(contents.as_bytes()).await;
+                                        }
+                                    });
+                                }
+                            }
+                        });
+
                         if ui.button("➡ Import Graph").clicked() {
                             ui.close_menu();
                             let sender = self.text_channel.0.clone();
@@ -384,11 +463,43 @@ impl eframe::App for App {
                         }
                     });
                     egui::menu::menu_button(ui, "Edit", |ui| {
+                        if ui
+                            .add_enabled(
+                                self.active_graph.can_undo(),
+                                egui::Button::new("⟲ Undo")
+                                    .shortcut_text(ui.ctx().format_shortcut(&UNDO_SHORTCUT)),
+                            )
+                            .clicked()
+                        {
+                            self.active_graph.undo();
+                        }
+                        if ui
+                            .add_enabled(
+                                self.active_graph.can_redo(),
+                                egui::Button::new("⟳ Redo")
+                                    .shortcut_text(ui.ctx().format_shortcut(&REDO_SHORTCUT)),
+                            )
+                            .clicked()
+                        {
+                            self.active_graph.redo();
+                        }
+
+                        ui.separator();
+
                         if ui.button("🗑 Delete Mode").clicked() {
                             ui.close_menu();
                             self.active_graph.editing_mode = graph::EditingMode::Delete;
                         }
                     });
+                    egui::menu::menu_button(ui, "Analyze", |ui| {
+                        if ui.button("🔍 Explore State Space").clicked() {
+                            ui.close_menu();
+                            self.analysis = None;
+                            self.exploration =
+                                Some(self.active_graph.start_exploration(MAX_ANALYSIS_STATES));
+                            self.show_analysis = true;
+                        }
+                    });
                     egui::menu::menu_button(ui, "View", |ui| {
                         if ui.button("[  ] Autofit Graph").clicked() {
                             self.active_graph.queue_autofit();
@@ -397,6 +508,9 @@ impl eframe::App for App {
                         ui.separator();
                         ui.checkbox(&mut self.pin_menu_bar, " Pin Menu Bar");
                         ui.checkbox(&mut self.show_simulation_controls, " Simulation Controls");
+                        ui.checkbox(&mut self.show_gantt_chart, " Gantt Chart");
+                        ui.checkbox(&mut self.show_statistics, " 📊 Statistics");
+                        ui.checkbox(&mut self.show_task_properties, " 📝 Task Properties");
                         ui.checkbox(&mut self.show_about_dialog, " ℹ About");
                     });
                     ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
@@ -444,6 +558,54 @@ impl eframe::App for App {
                         {
                             self.active_graph.editing_mode = graph::EditingMode::None;
                         }
+                        if let Some(deadlock) = self.active_graph.get_deadlock() {
+                            if ui
+                                .label(
+                                    egui::RichText::new(format!(
+                                        "Deadlock at tick {}: all {} activities are blocked by {} mutexes{}{}. Click here to dismiss.",
+                                        deadlock.tick,
+                                        deadlock.blocked_activities.len(),
+                                        deadlock.blocking_mutexes.len(),
+                                        match deadlock.blocking_barriers.len() {
+                                            0 => String::new(),
+                                            count => format!(", {} barriers", count),
+                                        },
+                                        match deadlock.blocking_queues.len() {
+                                            0 => String::new(),
+                                            count => format!(", {} queues", count),
+                                        }
+                                    ))
+                                    .color(egui::Color32::YELLOW),
+                                )
+                                .clicked()
+                            {
+                                self.active_graph.dismiss_deadlock();
+                            }
+                        }
+                        if let Some(deadline_miss) = self.active_graph.get_deadline_miss() {
+                            let activity_name = self
+                                .active_graph
+                                .get_activity_node(deadline_miss.activity)
+                                .map(|activity_node| {
+                                    format!(
+                                        "{}: {}",
+                                        activity_node.task_name, activity_node.activity_name
+                                    )
+                                })
+                                .unwrap_or_default();
+                            if ui
+                                .label(
+                                    egui::RichText::new(format!(
+                                        "{} missed its deadline at tick {}. Click here to dismiss.",
+                                        activity_name, deadline_miss.tick,
+                                    ))
+                                    .color(egui::Color32::YELLOW),
+                                )
+                                .clicked()
+                            {
+                                self.active_graph.dismiss_deadline_miss();
+                            }
+                        }
                         ui.centered_and_justified(|ui| {
                             ui.add(
                                 egui::TextEdit::singleline(&mut self.active_graph.name)
@@ -458,6 +620,23 @@ impl eframe::App for App {
         egui::TopBottomPanel::bottom("bottom_panel")
             .min_height(25.)
             .show_animated(ctx, self.show_simulation_controls, |ui| {
+                if let Some(timeline_range) = self.active_graph.get_timeline_range() {
+                    ui.add_space(4.);
+                    ui.horizontal(|ui| {
+                        ui.style_mut().spacing.slider_width = ui.available_width() - 100.;
+                        let mut tick = self.active_graph.get_current_tick();
+                        if ui
+                            .add_enabled(
+                                timeline_range.start() < timeline_range.end(),
+                                egui::widgets::Slider::new(&mut tick, timeline_range).text("tick"),
+                            )
+                            .changed()
+                        {
+                            self.active_graph.rewind_to(tick);
+                        }
+                    });
+                    ui.separator();
+                }
                 ui.horizontal_centered(|ui| {
                     ui.style_mut().spacing.slider_width = 175.;
                     let response = ui.add(
@@ -474,6 +653,83 @@ impl eframe::App for App {
                         response.surrender_focus();
                     };
 
+                    ui.separator();
+                    ui.label("seed ");
+                    ui.add(
+                        egui::DragValue::new(&mut self.active_graph.seed)
+                            .update_while_editing(false),
+                    );
+                    if ui.button("Random Seed").clicked() {
+                        self.active_graph.seed = rand::random();
+                    }
+
+                    ui.separator();
+                    egui::ComboBox::from_label("tie breaking")
+                        .selected_text(self.active_graph.tie_breaking.name())
+                        .show_ui(ui, |ui| {
+                            for tie_breaking in graph::TieBreaking::ALL {
+                                ui.selectable_value(
+                                    &mut self.active_graph.tie_breaking,
+                                    tie_breaking,
+                                    tie_breaking.name(),
+                                );
+                            }
+                        });
+
+                    ui.separator();
+                    egui::ComboBox::from_label("policy")
+                        .selected_text(self.active_graph.scheduling_policy.name())
+                        .show_ui(ui, |ui| {
+                            for scheduling_policy in graph::SchedulingPolicy::ALL {
+                                ui.selectable_value(
+                                    &mut self.active_graph.scheduling_policy,
+                                    scheduling_policy,
+                                    scheduling_policy.name(),
+                                );
+                            }
+                        });
+                    if self.active_graph.scheduling_policy == graph::SchedulingPolicy::FixedPriority
+                    {
+                        egui::ComboBox::from_label("priority protocol")
+                            .selected_text(self.active_graph.priority_protocol.name())
+                            .show_ui(ui, |ui| {
+                                for priority_protocol in graph::PriorityProtocol::ALL {
+                                    ui.selectable_value(
+                                        &mut self.active_graph.priority_protocol,
+                                        priority_protocol,
+                                        priority_protocol.name(),
+                                    );
+                                }
+                            });
+                    }
+                    if self.active_graph.scheduling_policy == graph::SchedulingPolicy::RoundRobin {
+                        ui.label("quantum ");
+                        ui.add(
+                            egui::DragValue::new(&mut self.active_graph.quantum)
+                                .clamp_range(1..=graph::MAX_THREE_DIGIT_NUMBER),
+                        );
+                    }
+
+                    ui.separator();
+                    let mut limit_cores = self.active_graph.core_count.is_some();
+                    if ui.checkbox(&mut limit_cores, "cores ").changed() {
+                        self.active_graph.core_count = limit_cores.then_some(1);
+                    }
+                    if let Some(core_count) = &mut self.active_graph.core_count {
+                        ui.add(egui::DragValue::new(core_count).clamp_range(1..=64));
+                    }
+                    ui.add_enabled(
+                        self.active_graph.core_count.is_some(),
+                        egui::Checkbox::new(&mut self.active_graph.preemptive, "preemptive"),
+                    )
+                    .on_disabled_hover_text("Preemption requires a limited number of cores");
+
+                    ui.separator();
+                    ui.checkbox(
+                        &mut self.active_graph.pause_on_deadline_miss,
+                        "pause on deadline miss",
+                    );
+
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                         if ui
                             .add(
@@ -500,6 +756,18 @@ impl eframe::App for App {
                             if ui.button("Single Step").clicked() {
                                 self.active_graph.queue_tick();
                             }
+                            let current_tick = self.active_graph.get_current_tick();
+                            if ui
+                                .add_enabled(
+                                    self.active_graph
+                                        .get_timeline_range()
+                                        .map_or(false, |range| *range.start() < current_tick),
+                                    egui::Button::new("Step Back"),
+                                )
+                                .clicked()
+                            {
+                                self.active_graph.rewind_to(current_tick - 1);
+                            }
                             ui.separator();
                             ui.label("ticks remaining");
                             if ui
@@ -520,6 +788,22 @@ impl eframe::App for App {
                 });
             });
 
+        egui::TopBottomPanel::bottom("gantt_panel")
+            .resizable(true)
+            .default_height(150.)
+            .min_height(80.)
+            .show_animated(ctx, self.show_gantt_chart, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Gantt Chart");
+                    ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                        ui.checkbox(&mut self.gantt_chart_group_by_task, " Group by Task");
+                    });
+                });
+                ui.separator();
+                self.active_graph
+                    .gantt_chart_ui(ui, self.gantt_chart_group_by_task);
+            });
+
         egui::SidePanel::left("about_panel")
             .resizable(true)
             .default_width(350.)
@@ -587,6 +871,162 @@ impl eframe::App for App {
                 });
             });
 
+        egui::SidePanel::right("statistics_panel")
+            .resizable(true)
+            .default_width(400.)
+            .min_width(250.)
+            .max_width(700.)
+            .show_animated(ctx, self.show_statistics, |ui| {
+                // pinned close button
+                let right_top = ui.available_rect_before_wrap().right_top();
+                let rect = egui::Rect::from_points(&[right_top, right_top + egui::vec2(-20., 20.)]);
+                ui.painter().text(
+                    rect.center(),
+                    egui::Align2::CENTER_CENTER,
+                    "❌",
+                    egui::FontId::proportional(15.),
+                    egui::Color32::GRAY,
+                );
+                if ui.input(|i| i.pointer.primary_clicked()) && ui.rect_contains_pointer(rect) {
+                    self.show_statistics = false;
+                }
+
+                ui.heading("Statistics");
+                egui::scroll_area::ScrollArea::vertical()
+                    .auto_shrink(false)
+                    .show(ui, |ui| {
+                        self.active_graph.statistics_ui(ui);
+                    });
+            });
+
+        egui::Window::new("Task Properties")
+            .open(&mut self.show_task_properties)
+            .default_width(450.)
+            .show(ctx, |ui| {
+                self.active_graph.task_properties_ui(ui);
+            });
+
+        if let Some(exploration) = &mut self.exploration {
+            match exploration.step(ANALYSIS_STATES_PER_FRAME) {
+                true => self.analysis = self.exploration.take().map(|e| e.into_analysis()),
+                false => ctx.request_repaint(),
+            }
+        }
+        if let Some(exploration) = &self.exploration {
+            let mut cancel = !self.show_analysis;
+            egui::Window::new("State Space")
+                .open(&mut self.show_analysis)
+                .default_width(300.)
+                .show(ctx, |ui| {
+                    let (explored_states, queued_states) = exploration.progress();
+                    ui.label(format!(
+                        "{} states explored, {} waiting",
+                        explored_states, queued_states
+                    ));
+                    ui.add(
+                        egui::ProgressBar::new(
+                            explored_states as f32 / exploration.max_states() as f32,
+                        )
+                        .animate(true),
+                    );
+                    cancel |= ui.button("Cancel").clicked();
+                });
+            if cancel {
+                self.analysis = self.exploration.take().map(|e| e.into_analysis());
+            }
+        } else if let Some(analysis) = &self.analysis {
+            egui::Window::new("State Space")
+                .open(&mut self.show_analysis)
+                .default_width(300.)
+                .show(ctx, |ui| {
+                    ui.label(format!(
+                        "{} reachable states, {} transitions",
+                        analysis.state_count, analysis.transition_count
+                    ));
+                    if !analysis.complete {
+                        ui.colored_label(
+                            egui::Color32::YELLOW,
+                            format!(
+                                "Stopped after {} states, the results are incomplete.",
+                                analysis.state_count
+                            ),
+                        );
+                    }
+
+                    ui.separator();
+                    match analysis.deadlock_states.len() {
+                        0 => ui.label("No reachable deadlocks."),
+                        count => ui.colored_label(
+                            egui::Color32::YELLOW,
+                            format!("{} reachable deadlock states", count),
+                        ),
+                    };
+                    egui::scroll_area::ScrollArea::vertical()
+                        .max_height(150.)
+                        .show(ui, |ui| {
+                            for state in &analysis.deadlock_states {
+                                ui.label(format!(
+                                    "Mutex values: {}",
+                                    state
+                                        .mutexes
+                                        .iter()
+                                        .map(|(id, mutex_state)| format!(
+                                            "{}: {}",
+                                            id, mutex_state.value
+                                        ))
+                                        .collect::<Vec<_>>()
+                                        .join(", ")
+                                ));
+                            }
+                        });
+
+                    ui.separator();
+                    egui::Grid::new("mutex_bounds")
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.label("Mutex");
+                            ui.label("Maximum Value");
+                            ui.end_row();
+                            for (id, max_value) in &analysis.max_mutex_values {
+                                ui.label(id.to_string());
+                                ui.label(max_value.to_string());
+                                ui.end_row();
+                            }
+                        });
+
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        ui.label("Can mutex ");
+                        egui::ComboBox::from_id_source("analysis_mutex")
+                            .width(50.)
+                            .selected_text(
+                                self.analysis_mutex
+                                    .map(|id| id.to_string())
+                                    .unwrap_or_default(),
+                            )
+                            .show_ui(ui, |ui| {
+                                for id in analysis.max_mutex_values.keys() {
+                                    ui.selectable_value(
+                                        &mut self.analysis_mutex,
+                                        Some(*id),
+                                        id.to_string(),
+                                    );
+                                }
+                            });
+                        ui.label(" exceed ");
+                        ui.add(egui::DragValue::new(&mut self.analysis_bound));
+                        ui.label("? ");
+                        if let Some(mutex_id) = self.analysis_mutex {
+                            ui.label(match analysis.can_exceed(mutex_id, self.analysis_bound) {
+                                Some(true) => "Yes",
+                                Some(false) => "No",
+                                None => "Unknown",
+                            });
+                        }
+                    });
+                });
+        }
+
         // main panel
         egui::CentralPanel::default().show(ctx, |ui| {
             ui.centered_and_justified(|ui| {
@@ -0,0 +1,435 @@
+#![warn(clippy::all, rust_2018_idioms)]
+
+use std::io::Write as _;
+
+const USAGE: &str = "Usage: tsyncs-cli <graph.csv> [--ticks <n>] [--seed <n>]
+                  [--tie-breaking <rule>] [--policy <policy>]
+                  [--priority-protocol <protocol>] [--quantum <n>]
+                  [--cores <n>] [--preemptive] [--stop-on-deadline-miss]
+                  [--trace <trace.csv>] [--events <events.csv|events.json>]
+
+Loads a graph exported from tsyncs, runs the simulation for the given number
+of ticks (default: 1) without opening a window and prints the final state.
+The simulation stops early with exit code 2 if it runs into a deadlock.
+
+Options:
+    -t, --ticks <n>       number of ticks to simulate
+    -s, --seed <n>        override the seed stored in the graph
+    --tie-breaking <rule> override the tie breaking rule stored in the graph
+                          (random, lowest id, round robin)
+    --policy <policy>     override the scheduling policy stored in the graph
+                          (fixed priority, earliest deadline first,
+                          rate monotonic, round robin)
+    --priority-protocol <protocol>
+                          override the priority protocol stored in the graph
+                          (none, inheritance, ceiling)
+    --quantum <n>         override the round robin quantum stored in the graph
+    -c, --cores <n>       limit the number of activities running at the same time
+    -p, --preemptive      let higher priorities suspend running activities
+    --stop-on-deadline-miss
+                          stop at the first missed deadline
+    -o, --trace <file>    write the state after every tick to a CSV file
+    -e, --events <file>   write every simulation event to a CSV or JSON file
+    -h, --help            print this help";
+
+struct Options {
+    graph_path: String,
+    ticks: u32,
+    seed: Option<u32>,
+    tie_breaking: Option<tsyncs::TieBreaking>,
+    scheduling_policy: Option<tsyncs::SchedulingPolicy>,
+    priority_protocol: Option<tsyncs::PriorityProtocol>,
+    quantum: Option<u32>,
+    core_count: Option<u32>,
+    preemptive: bool,
+    stop_on_deadline_miss: bool,
+    trace_path: Option<String>,
+    events_path: Option<String>,
+}
+
+fn parse_options(mut args: impl Iterator<Item = String>) -> Result<Option<Options>, String> {
+    let mut graph_path = None;
+    let mut ticks = 1;
+    let mut seed = None;
+    let mut tie_breaking = None;
+    let mut scheduling_policy = None;
+    let mut priority_protocol = None;
+    let mut quantum = None;
+    let mut core_count = None;
+    let mut preemptive = false;
+    let mut stop_on_deadline_miss = false;
+    let mut trace_path = None;
+    let mut events_path = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "-t" | "--ticks" => {
+                let value = args.next().ok_or("Missing value for --ticks")?;
+                ticks = value
+                    .parse::<u32>()
+                    .map_err(|_| format!("Invalid number of ticks: {}", value))?;
+            }
+            "-s" | "--seed" => {
+                let value = args.next().ok_or("Missing value for --seed")?;
+                seed = Some(
+                    value
+                        .parse::<u32>()
+                        .map_err(|_| format!("Invalid seed: {}", value))?,
+                );
+            }
+            "--tie-breaking" => {
+                let value = args.next().ok_or("Missing value for --tie-breaking")?;
+                tie_breaking = Some(
+                    tsyncs::TieBreaking::from_name(&value)
+                        .ok_or_else(|| format!("Unknown tie breaking rule: {}", value))?,
+                );
+            }
+            "--policy" => {
+                let value = args.next().ok_or("Missing value for --policy")?;
+                scheduling_policy = Some(
+                    tsyncs::SchedulingPolicy::from_name(&value)
+                        .ok_or_else(|| format!("Unknown scheduling policy: {}", value))?,
+                );
+            }
+            "--priority-protocol" => {
+                let value = args.next().ok_or("Missing value for --priority-protocol")?;
+                priority_protocol = Some(
+                    tsyncs::PriorityProtocol::from_name(&value)
+                        .ok_or_else(|| format!("Unknown priority protocol: {}", value))?,
+                );
+            }
+            "--quantum" => {
+                let value = args.next().ok_or("Missing value for --quantum")?;
+                quantum = Some(
+                    value
+                        .parse::<u32>()
+                        .ok()
+                        .filter(|quantum| *quantum > 0)
+                        .ok_or_else(|| format!("Invalid quantum: {}", value))?,
+                );
+            }
+            "-c" | "--cores" => {
+                let value = args.next().ok_or("Missing value for --cores")?;
+                core_count = Some(
+                    value
+                        .parse::<u32>()
+                        .ok()
+                        .filter(|cores| *cores > 0)
+                        .ok_or_else(|| format!("Invalid number of cores: {}", value))?,
+                );
+            }
+            "-p" | "--preemptive" => preemptive = true,
+            "--stop-on-deadline-miss" => stop_on_deadline_miss = true,
+            "-o" | "--trace" => {
+                trace_path = Some(args.next().ok_or("Missing value for --trace")?);
+            }
+            "-e" | "--events" => {
+                events_path = Some(args.next().ok_or("Missing value for --events")?);
+            }
+            _ if arg.starts_with('-') => return Err(format!("Unknown option: {}", arg)),
+            _ if graph_path.is_none() => graph_path = Some(arg),
+            _ => return Err(format!("Unexpected argument: {}", arg)),
+        }
+    }
+
+    Ok(Some(Options {
+        graph_path: graph_path.ok_or("Missing graph file")?,
+        ticks,
+        seed,
+        tie_breaking,
+        scheduling_policy,
+        priority_protocol,
+        quantum,
+        core_count,
+        preemptive,
+        stop_on_deadline_miss,
+        trace_path,
+        events_path,
+    }))
+}
+
+fn write_trace_header(
+    trace: &mut impl std::io::Write,
+    graph: &tsyncs::Graph,
+) -> std::io::Result<()> {
+    let mut columns = vec!["Tick".to_string()];
+    columns.extend(sorted_activity_ids(graph).map(|id| format!("Activity {}", *id)));
+    columns.extend(sorted_mutex_ids(graph).map(|id| format!("Mutex {}", *id)));
+    writeln!(trace, "{}", columns.join(";"))
+}
+
+fn write_trace_line(
+    trace: &mut impl std::io::Write,
+    graph: &tsyncs::Graph,
+    tick: u32,
+) -> std::io::Result<()> {
+    let mut columns = vec![tick.to_string()];
+    columns.extend(sorted_activity_ids(graph).filter_map(|id| {
+        graph
+            .get_activity_node(id)
+            .map(|node| node.remaining_duration.to_string())
+    }));
+    columns.extend(
+        sorted_mutex_ids(graph)
+            .filter_map(|id| graph.get_mutex_node(id).map(|node| node.value.to_string())),
+    );
+    writeln!(trace, "{}", columns.join(";"))
+}
+
+fn sorted_activity_ids(graph: &tsyncs::Graph) -> impl Iterator<Item = tsyncs::ActivityNodeId> {
+    let mut ids = graph.activity_nodes().map(|(id, _)| id).collect::<Vec<_>>();
+    ids.sort();
+    ids.into_iter()
+}
+
+fn sorted_mutex_ids(graph: &tsyncs::Graph) -> impl Iterator<Item = tsyncs::MutexNodeId> {
+    let mut ids = graph.mutex_nodes().map(|(id, _)| id).collect::<Vec<_>>();
+    ids.sort();
+    ids.into_iter()
+}
+
+fn print_state(graph: &tsyncs::Graph, ticks: u32) {
+    println!("State after {} ticks", ticks);
+    println!();
+    if let Some(deadlock) = graph.get_deadlock() {
+        println!(
+            "Deadlock at tick {}: activities {} are blocked by mutexes {}",
+            deadlock.tick,
+            join_ids(deadlock.blocked_activities.iter().map(|id| **id)),
+            join_ids(deadlock.blocking_mutexes.iter().map(|id| **id))
+        );
+        if !deadlock.blocking_barriers.is_empty() {
+            println!(
+                "Blocking barriers: {}",
+                join_ids(deadlock.blocking_barriers.iter().map(|id| **id))
+            );
+        }
+        if !deadlock.blocking_queues.is_empty() {
+            println!(
+                "Blocking queues: {}",
+                join_ids(deadlock.blocking_queues.iter().map(|id| **id))
+            );
+        }
+        println!();
+    }
+    if let Some(deadline_miss) = graph.get_deadline_miss() {
+        println!(
+            "First deadline miss at tick {}: activity {}",
+            deadline_miss.tick, *deadline_miss.activity
+        );
+        println!();
+    }
+    println!("Activities:");
+    println!("ID;Task Name;Activity Name;Priority;Duration;Remaining Duration");
+    for id in sorted_activity_ids(graph) {
+        if let Some(node) = graph.get_activity_node(id) {
+            println!(
+                "{};{};{};{};{};{}",
+                *id,
+                node.task_name,
+                node.activity_name,
+                node.priority,
+                node.duration,
+                node.remaining_duration
+            );
+        }
+    }
+    println!();
+    println!("Mutexes:");
+    println!("ID;Mutex Value");
+    for id in sorted_mutex_ids(graph) {
+        if let Some(node) = graph.get_mutex_node(id) {
+            println!("{};{}", *id, node.value);
+        }
+    }
+    let mut barrier_ids = graph.barrier_nodes().map(|(id, _)| id).collect::<Vec<_>>();
+    barrier_ids.sort();
+    if !barrier_ids.is_empty() {
+        println!();
+        println!("Barriers:");
+        println!("ID;Arrivals;Count");
+        for id in barrier_ids {
+            if let Some(node) = graph.get_barrier_node(id) {
+                println!("{};{};{}", *id, node.arrived.len(), node.count);
+            }
+        }
+    }
+    let mut lock_ids = graph.lock_nodes().map(|(id, _)| id).collect::<Vec<_>>();
+    lock_ids.sort();
+    if !lock_ids.is_empty() {
+        println!();
+        println!("Locks:");
+        println!("ID;Holders");
+        for id in lock_ids {
+            println!(
+                "{};{}",
+                *id,
+                join_ids(graph.lock_holders(id).into_iter().map(|(id, _)| *id))
+            );
+        }
+    }
+    let mut queue_ids = graph.queue_nodes().map(|(id, _)| id).collect::<Vec<_>>();
+    queue_ids.sort();
+    if !queue_ids.is_empty() {
+        println!();
+        println!("Queues:");
+        println!("ID;Tokens");
+        for id in queue_ids {
+            if let Some(node) = graph.get_queue_node(id) {
+                println!(
+                    "{};{}",
+                    *id,
+                    node.tokens.iter().cloned().collect::<Vec<_>>().join(" ")
+                );
+            }
+        }
+    }
+}
+
+fn join_ids(ids: impl Iterator<Item = usize>) -> String {
+    ids.map(|id| id.to_string()).collect::<Vec<_>>().join(", ")
+}
+
+// returns false if the simulation ended in a deadlock
+fn run(options: Options) -> Result<bool, String> {
+    let text = std::fs::read_to_string(&options.graph_path)
+        .map_err(|e| format!("Failed to read {}: {}", options.graph_path, e))?;
+    let mut graph =
+        tsyncs::Graph::from_csv(&text).map_err(|e| format!("Failed to import graph: {}", e))?;
+    if let Some(seed) = options.seed {
+        graph.seed = seed;
+    }
+    if let Some(tie_breaking) = options.tie_breaking {
+        graph.tie_breaking = tie_breaking;
+    }
+    if let Some(scheduling_policy) = options.scheduling_policy {
+        graph.scheduling_policy = scheduling_policy;
+    }
+    if let Some(priority_protocol) = options.priority_protocol {
+        graph.priority_protocol = priority_protocol;
+    }
+    if let Some(quantum) = options.quantum {
+        graph.quantum = quantum;
+    }
+    if options.core_count.is_some() {
+        graph.core_count = options.core_count;
+    }
+    if options.preemptive {
+        graph.preemptive = true;
+    }
+    if options.stop_on_deadline_miss {
+        graph.pause_on_deadline_miss = true;
+    }
+    // without a core limit every ready activity runs right away, so nothing is ever preempted
+    if graph.preemptive && graph.core_count.is_none() {
+        eprintln!("Warning: preemption has no effect without a core limit, use --cores");
+    }
+
+    let executed_ticks = match &options.trace_path {
+        Some(trace_path) => {
+            let trace_error = |e: std::io::Error| format!("Failed to write {}: {}", trace_path, e);
+            let file = std::fs::File::create(trace_path).map_err(trace_error)?;
+            let mut trace = std::io::BufWriter::new(file);
+            write_trace_header(&mut trace, &graph).map_err(trace_error)?;
+            write_trace_line(&mut trace, &graph, 0).map_err(trace_error)?;
+            let mut executed_ticks = 0;
+            graph
+                .run_with(options.ticks, |graph| {
+                    executed_ticks += 1;
+                    write_trace_line(&mut trace, graph, executed_ticks)
+                })
+                .map_err(trace_error)?;
+            trace.flush().map_err(trace_error)?;
+            executed_ticks
+        }
+        None => graph.run(options.ticks),
+    };
+
+    if let Some(events_path) = &options.events_path {
+        let contents = match events_path.to_lowercase().ends_with(".json") {
+            true => graph.get_trace().to_json(),
+            false => graph.get_trace().to_csv(),
+        };
+        std::fs::write(events_path, contents)
+            .map_err(|e| format!("Failed to write {}: {}", events_path, e))?;
+    }
+
+    print_state(&graph, executed_ticks);
+    Ok(graph.get_deadlock().is_none())
+}
+
+fn main() -> std::process::ExitCode {
+    match parse_options(std::env::args().skip(1)) {
+        Ok(Some(options)) => match run(options) {
+            Ok(true) => std::process::ExitCode::SUCCESS,
+            Ok(false) => std::process::ExitCode::from(2),
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::ExitCode::FAILURE
+            }
+        },
+        Ok(None) => {
+            println!("{}", USAGE);
+            std::process::ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            std::process::ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_options, USAGE};
+
+    fn args(args: &[&str]) -> impl Iterator<Item = String> {
+        args.iter()
+            .map(|arg| arg.to_string())
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    #[test]
+    fn parses_graph_ticks_and_trace() {
+        let options = parse_options(args(&["graph.csv", "-t", "20", "--trace", "out.csv"]))
+            .unwrap()
+            .unwrap();
+        assert_eq!(options.graph_path, "graph.csv");
+        assert_eq!(options.ticks, 20);
+        assert_eq!(options.trace_path.as_deref(), Some("out.csv"));
+
+        let options = parse_options(args(&["graph.csv"])).unwrap().unwrap();
+        assert_eq!(options.ticks, 1);
+        assert!(options.trace_path.is_none());
+    }
+
+    #[test]
+    fn help_skips_the_simulation() {
+        assert!(parse_options(args(&["graph.csv", "--help"]))
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn rejects_invalid_arguments() {
+        assert!(parse_options(args(&[])).is_err());
+        assert!(parse_options(args(&["graph.csv", "--ticks"])).is_err());
+        assert!(parse_options(args(&["graph.csv", "--ticks", "-1"])).is_err());
+        assert!(parse_options(args(&["graph.csv", "--unknown"])).is_err());
+        assert!(parse_options(args(&["a.csv", "b.csv"])).is_err());
+    }
+
+    #[test]
+    fn synopsis_lists_every_option() {
+        let (synopsis, options) = USAGE.split_once("Options:").unwrap();
+        for option in options
+            .split_whitespace()
+            .filter(|word| word.starts_with("--") && *word != "--help")
+        {
+            let option = option.trim_end_matches(',');
+            assert!(synopsis.contains(option), "{option} is missing");
+        }
+    }
+}
@@ -1,4 +1,9 @@
 #![warn(clippy::all, rust_2018_idioms)]
 
 mod app;
+pub use app::graph::{
+    connection::Direction, ActivityNode, ActivityNodeId, Analysis, BarrierNode, BarrierNodeId,
+    Deadlock, Exploration, Graph, LockAccess, LockNode, LockNodeId, MutexNode, MutexNodeId,
+    PriorityProtocol, QueueNode, QueueNodeId, SchedulingPolicy, TieBreaking,
+};
 pub use app::App;
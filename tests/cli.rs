@@ -0,0 +1,72 @@
+// runs the command line tool on a graph written to a temporary file
+fn run_cli(name: &str, graph: &str, args: &[&str]) -> std::process::Output {
+    let path = std::env::temp_dir().join(format!("tsyncs-cli-{}-{name}.csv", std::process::id()));
+    std::fs::write(&path, graph).unwrap();
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_tsyncs-cli"))
+        .arg(&path)
+        .args(args)
+        .output()
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+    output
+}
+
+#[test]
+fn finished_run_exits_successfully() {
+    let output = run_cli(
+        "finished",
+        "Task;0;0;0;A;a;0;1;0;0\nMutex;0;0;0;1;0\n",
+        &["--ticks", "5"],
+    );
+    assert_eq!(output.status.code(), Some(0));
+}
+
+#[test]
+fn deadlock_exits_with_code_2() {
+    let output = run_cli(
+        "deadlock",
+        "Task;0;0;0;A;a;0;1;0\nMutex;0;0;0;0;0\n",
+        &["--ticks", "5"],
+    );
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn invalid_arguments_exit_with_code_1() {
+    let output = run_cli("invalid", "", &["--ticks", "many"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Usage"));
+}
+
+#[test]
+fn trace_stops_at_a_deadlock() {
+    let trace_path = std::env::temp_dir().join(format!(
+        "tsyncs-cli-{}-trace-output.csv",
+        std::process::id()
+    ));
+    let output = run_cli(
+        "trace",
+        "Task;0;0;0;A;a;0;1;0\nMutex;0;0;0;1;0\n",
+        &["--ticks", "10", "--trace", trace_path.to_str().unwrap()],
+    );
+    let trace = std::fs::read_to_string(&trace_path).unwrap();
+    std::fs::remove_file(&trace_path).unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    // the header, the initial state and the two executed ticks
+    assert_eq!(trace.lines().count(), 4);
+}
+
+#[test]
+fn preemption_without_cores_warns() {
+    let graph = "Task;0;0;0;A;a;0;1;0;0\nMutex;0;0;0;1;0\n";
+    let output = run_cli("preemptive", graph, &["--ticks", "1", "--preemptive"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Warning"));
+
+    let output = run_cli(
+        "preemptive-cores",
+        graph,
+        &["--ticks", "1", "--preemptive", "--cores", "1"],
+    );
+    assert!(output.stderr.is_empty());
+}